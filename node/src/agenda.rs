@@ -0,0 +1,631 @@
+//! Agenda subcommands: moving contacts between vCard files and the chain, and appointments
+//! between iCalendar files and the chain.
+
+mod conteudo;
+mod envio;
+pub mod ical;
+pub mod vcard;
+
+use std::{
+	fs,
+	io::Write,
+	path::PathBuf,
+	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use gcc1919_agenda_pallets::{
+	busca, runtime_api::AgendaApi, Call as AgendaCall, Categoria, Compromisso, Contato, FiltroCompromissos,
+	FiltroContatos, Local, ModoLote, NovoCompromisso, NovoContato, OrdemCompromissos, OrdemContatos, Prioridade,
+	Status, Tarefa, MAX_PAGINA,
+};
+use sc_cli::{BlockNumberOrHash, CliConfiguration, DatabaseParams, SharedParams};
+use solochain_template_runtime::{opaque::Block, AccountId, Runtime, RuntimeCall};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{sr25519, Get, Pair, H256};
+use sp_runtime::BoundedVec;
+
+type MaxLote = <Runtime as gcc1919_agenda_pallets::Config>::MaxLote;
+
+/// Agenda utilities.
+#[derive(Debug, clap::Subcommand)]
+pub enum AgendaCmd {
+	/// Export an account's contacts at a given block to a vCard file.
+	ExportVcard(ExportVcardCmd),
+
+	/// Import contacts from a vCard 3.0/4.0 file.
+	ImportVcard(ImportVcardCmd),
+
+	/// Export an account's appointments at a given block to an iCalendar file.
+	ExportIcs(ExportIcsCmd),
+
+	/// Import appointments from the events of an iCalendar file.
+	ImportIcs(ImportIcsCmd),
+}
+
+/// The `agenda export-vcard` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportVcardCmd {
+	/// SS58 address of the account whose contacts are exported.
+	#[arg(long)]
+	pub account: String,
+
+	/// Block hash or number to read the contacts at. Defaults to the best block.
+	#[arg(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// File to write the vCards to. Printed to stdout when omitted.
+	#[arg(long, short)]
+	pub output: Option<PathBuf>,
+
+	/// vCard version to write.
+	#[arg(long, value_enum, default_value = "4.0")]
+	pub vcard_version: vcard::Versao,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+/// The `agenda import-vcard` command.
+///
+/// Each card becomes one contact, sent in `criar_contatos` batch calls of up to `MaxLote` items.
+/// The pallet needs a full birthday, so cards without a `BDAY` year are reported and skipped.
+/// Every item counts against the account's write quota, so nothing is submitted when the file
+/// holds more contacts than the quota has room for.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ImportVcardCmd {
+	/// vCard file to read.
+	#[arg(value_name = "FILE")]
+	pub input: PathBuf,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub destino: DestinoParams,
+}
+
+/// The `agenda export-ics` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportIcsCmd {
+	/// SS58 address of the account whose appointments are exported.
+	#[arg(long)]
+	pub account: String,
+
+	/// Block hash or number to read the appointments at. Defaults to the best block.
+	#[arg(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// File to write the calendar to. Printed to stdout when omitted.
+	#[arg(long, short)]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+/// The `agenda import-ics` command.
+///
+/// Timed events become `criar_compromissos` batch calls and all-day events
+/// `criar_compromisso_dia_inteiro` calls. The pallet has no recurrence of its own, so recurring
+/// events are expanded into one appointment per occurrence; rules it can't expand import only
+/// their first occurrence. Cancelled events are skipped.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ImportIcsCmd {
+	/// iCalendar file to read.
+	#[arg(value_name = "FILE")]
+	pub input: PathBuf,
+
+	/// Most occurrences imported for each recurring event.
+	#[arg(long, default_value_t = 52)]
+	pub max_occurrences: usize,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub destino: DestinoParams,
+}
+
+/// Where the imports send their calls: signed extrinsics submitted to a node, or a file.
+#[derive(Debug, Clone, clap::Args)]
+pub struct DestinoParams {
+	/// Write the SCALE-encoded calls to this file, one hex line per call, instead of submitting.
+	#[arg(long, short, conflicts_with = "suri")]
+	pub output: Option<PathBuf>,
+
+	/// Secret URI of the account the records are created for, e.g. `//Alice`.
+	#[arg(long, required_unless_present = "output")]
+	pub suri: Option<String>,
+
+	/// WebSocket RPC endpoint of the node to submit to.
+	#[arg(long, default_value = "ws://127.0.0.1:9944")]
+	pub url: String,
+
+	/// Roll back a whole batch when one of its items fails.
+	#[arg(long)]
+	pub all_or_nothing: bool,
+}
+
+impl DestinoParams {
+	fn modo(&self) -> ModoLote {
+		if self.all_or_nothing {
+			ModoLote::TudoOuNada
+		} else {
+			ModoLote::MelhorEsforco
+		}
+	}
+
+	/// Writes `chamadas` to `--output`, or signs them with `--suri` and submits them to `--url`.
+	fn entregar(&self, chamadas: Vec<RuntimeCall>) -> sc_cli::Result<()> {
+		if let Some(saida) = &self.output {
+			envio::gravar_chamadas(saida, &chamadas)?;
+			eprintln!("Wrote {} calls to {}", chamadas.len(), saida.display());
+			return Ok(());
+		}
+
+		let suri = self.suri.as_deref().ok_or("either --suri or --output is required")?;
+		let par = sr25519::Pair::from_string(suri, None).map_err(|e| format!("invalid --suri: {:?}", e))?;
+		let hashes = sc_cli::build_runtime()?.block_on(envio::enviar(&self.url, &par, chamadas))?;
+		for hash in &hashes {
+			println!("{:?}", hash);
+		}
+		eprintln!("Submitted {} extrinsics to {}", hashes.len(), self.url);
+		Ok(())
+	}
+}
+
+impl ExportVcardCmd {
+	/// Reads every contact of the account at the chosen block and writes them out as vCards.
+	pub fn run<C>(&self, client: Arc<C>) -> sc_cli::Result<()>
+	where
+		C: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+		C::Api: AgendaApi<Block, AccountId, Contato<Runtime>, Compromisso<Runtime>, Tarefa<Runtime>>,
+	{
+		let (conta, at) = conta_e_bloco(&self.account, self.at.as_ref(), client.as_ref())?;
+		let api = client.runtime_api();
+		let mut cartoes = Vec::new();
+		let mut apos = None;
+		loop {
+			let pagina = api
+				.listar_contatos(at, conta.clone(), FiltroContatos::default(), OrdemContatos::Nome, apos, MAX_PAGINA)
+				.map_err(|e| format!("listing contacts at {:?}: {}", at, e))?;
+			cartoes.extend(pagina.itens.into_iter().map(cartao_de_contato));
+			match pagina.proximo {
+				Some(cursor) => apos = Some(cursor),
+				None => break,
+			}
+		}
+
+		let texto = vcard::escrever(&cartoes, self.vcard_version);
+		match &self.output {
+			Some(caminho) => fs::write(caminho, texto)?,
+			None => std::io::stdout().write_all(texto.as_bytes())?,
+		}
+		eprintln!("Exported {} contacts of {} at {:?}", cartoes.len(), self.account, at);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ExportVcardCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+impl ImportVcardCmd {
+	/// Reads the vCard file and submits or writes out the contacts.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let texto = fs::read_to_string(&self.input)?;
+		let hoje = data_civil(agora());
+
+		let mut novos = Vec::new();
+		for cartao in vcard::ler(&texto).map_err(|e| format!("{}: {}", self.input.display(), e))? {
+			let nome = cartao.nome.clone();
+			match novo_contato(cartao, hoje) {
+				Ok(novo) => novos.push(novo),
+				Err(motivo) => eprintln!("Skipping `{}`: {}", nome, motivo),
+			}
+		}
+
+		let modo = self.destino.modo();
+		self.destino.entregar(em_lotes(&novos, |itens| AgendaCall::criar_contatos { itens, modo }))
+	}
+}
+
+impl ExportIcsCmd {
+	/// Reads every appointment of the account at the chosen block and writes them out as events.
+	pub fn run<C>(&self, client: Arc<C>) -> sc_cli::Result<()>
+	where
+		C: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+		C::Api: AgendaApi<Block, AccountId, Contato<Runtime>, Compromisso<Runtime>, Tarefa<Runtime>>,
+	{
+		let (conta, at) = conta_e_bloco(&self.account, self.at.as_ref(), client.as_ref())?;
+		let api = client.runtime_api();
+		let mut eventos = Vec::new();
+		let mut apos = None;
+		loop {
+			let pagina = api
+				.listar_compromissos(
+					at,
+					conta.clone(),
+					FiltroCompromissos::default(),
+					OrdemCompromissos::Inicio,
+					apos,
+					MAX_PAGINA,
+				)
+				.map_err(|e| format!("listing appointments at {:?}: {}", at, e))?;
+			eventos.extend(pagina.itens.into_iter().map(|compromisso| evento_de_compromisso(compromisso, &self.account)));
+			match pagina.proximo {
+				Some(cursor) => apos = Some(cursor),
+				None => break,
+			}
+		}
+
+		let agora = agora();
+		let (ano, mes, dia) = data_civil(agora);
+		let segundos = agora % 86_400;
+		let carimbo = format!(
+			"{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+			ano,
+			mes,
+			dia,
+			segundos / 3_600,
+			segundos / 60 % 60,
+			segundos % 60
+		);
+		let texto = ical::escrever(&eventos, &carimbo);
+		match &self.output {
+			Some(caminho) => fs::write(caminho, texto)?,
+			None => std::io::stdout().write_all(texto.as_bytes())?,
+		}
+		eprintln!("Exported {} appointments of {} at {:?}", eventos.len(), self.account, at);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ExportIcsCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+impl ImportIcsCmd {
+	/// Reads the calendar, expands recurring events and submits or writes out the appointments.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let texto = fs::read_to_string(&self.input)?;
+		let eventos = ical::ler(&texto).map_err(|e| format!("{}: {}", self.input.display(), e))?;
+		let min_duracao = <Runtime as gcc1919_agenda_pallets::Config>::MinDuracao::get();
+		let max_duracao = <Runtime as gcc1919_agenda_pallets::Config>::MaxDuracao::get();
+
+		let mut com_horario = Vec::new();
+		let mut dia_inteiro = Vec::new();
+		for evento in eventos {
+			if evento.status.as_deref() == Some("CANCELLED") {
+				eprintln!("Skipping cancelled `{}`", evento.titulo);
+				continue;
+			}
+			if let ical::Quando::Horario { duracao, .. } = evento.quando {
+				if duracao > max_duracao {
+					eprintln!("Skipping `{}`: lasts more than {} minutes", evento.titulo, max_duracao);
+					continue;
+				}
+				// Zero-length markers and very short events are kept, lengthened to the pallet minimum
+				if duracao < min_duracao {
+					eprintln!(
+						"`{}`: lasts less than {} minutes, importing it with {} minutes",
+						evento.titulo, min_duracao, min_duracao
+					);
+				}
+			}
+			if let Some(Err(regra)) = &evento.recorrencia {
+				eprintln!("`{}`: unsupported RRULE `{}`, importing only the first occurrence", evento.titulo, regra);
+			}
+			// The pallet counts dates from the Unix epoch
+			let ocorrencias = ical::ocorrencias(&evento, (1970, 1, 1), self.max_occurrences);
+			if ocorrencias.anteriores > 0 && ocorrencias.datas.is_empty() {
+				eprintln!("Skipping `{}`: dates before 1970 are not supported", evento.titulo);
+				continue;
+			}
+			if ocorrencias.anteriores > 0 {
+				eprintln!(
+					"`{}`: leaving out {} occurrences before 1970, which are not supported",
+					evento.titulo, ocorrencias.anteriores
+				);
+			}
+			if ocorrencias.truncado {
+				eprintln!("`{}`: importing only the first {} occurrences", evento.titulo, self.max_occurrences);
+			}
+
+			let titulo = titulo_do_evento(&evento.titulo);
+			let prioridade = prioridade_do_evento(evento.prioridade);
+			for data in ocorrencias.datas {
+				match evento.quando {
+					ical::Quando::Horario { minuto, duracao, .. } => com_horario.push(NovoCompromisso {
+						titulo: titulo.clone(),
+						data: data_texto(data),
+						hora: format!("{:02}:{:02}", minuto / 60, minuto % 60),
+						prioridade: prioridade.clone(),
+						duracao: duracao.max(min_duracao),
+					}),
+					ical::Quando::DiaInteiro { inicio, fim } => {
+						let dias = dia_da_data(fim) - dia_da_data(inicio);
+						let data_fim = data_do_dia(dia_da_data(data) + dias);
+						dia_inteiro.push(RuntimeCall::GCC1919AgendaPallet(AgendaCall::criar_compromisso_dia_inteiro {
+							titulo: titulo.clone(),
+							data: data_texto(data),
+							data_fim: (dias > 0).then(|| data_texto(data_fim)),
+							prioridade: prioridade.clone(),
+						}));
+					},
+				}
+			}
+		}
+
+		let modo = self.destino.modo();
+		let mut chamadas = em_lotes(&com_horario, |itens| AgendaCall::criar_compromissos { itens, modo });
+		chamadas.extend(dia_inteiro);
+		self.destino.entregar(chamadas)
+	}
+}
+
+// Turns `MaxLote`-sized chunks of `itens` into one batch call each
+fn em_lotes<Item: Clone + std::fmt::Debug>(
+	itens: &[Item],
+	chamada: impl Fn(BoundedVec<Item, MaxLote>) -> AgendaCall<Runtime>,
+) -> Vec<RuntimeCall> {
+	itens
+		.chunks(limite::<MaxLote>())
+		.map(|lote| {
+			let lote = lote.to_vec().try_into().expect("chunks are at most MaxLote long; qed");
+			RuntimeCall::GCC1919AgendaPallet(chamada(lote))
+		})
+		.collect()
+}
+
+// A length limit of the runtime, as a `usize` to compare against
+fn limite<L: Get<u32>>() -> usize {
+	L::get() as usize
+}
+
+fn conta_e_bloco<C: HeaderBackend<Block>>(
+	conta: &str,
+	at: Option<&BlockNumberOrHash>,
+	client: &C,
+) -> sc_cli::Result<(AccountId, H256)> {
+	let conta = conta.parse().map_err(|e| format!("invalid --account `{}`: {}", conta, e))?;
+	let at = match at {
+		Some(bloco) => client.expect_block_hash_from_id(&bloco.parse::<Block>()?)?,
+		None => client.info().best_hash,
+	};
+	Ok((conta, at))
+}
+
+fn agora() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+// The pallet requires a full birthday, so cards without one, with only month and day or from
+// before 1970 are rejected rather than given a made-up date.
+fn novo_contato(cartao: vcard::Cartao, hoje: Data) -> Result<NovoContato, String> {
+	let max_nome = limite::<<Runtime as gcc1919_agenda_pallets::Config>::MaxNomeLength>();
+	let max_telefone = limite::<<Runtime as gcc1919_agenda_pallets::Config>::MaxTelefoneLength>();
+	let max_email = limite::<<Runtime as gcc1919_agenda_pallets::Config>::MaxEmailLength>();
+	let max_notas = limite::<<Runtime as gcc1919_agenda_pallets::Config>::MaxNotasLength>();
+
+	if cartao.nome.len() > max_nome {
+		return Err(format!("name is longer than {} bytes", max_nome));
+	}
+	let primeiro_que_cabe = |valores: &[String], maximo: usize, campo: &str| {
+		let escolhido = valores.iter().find(|valor| valor.len() <= maximo).cloned();
+		if escolhido.is_none() && !valores.is_empty() {
+			eprintln!("`{}`: every {} is longer than {} bytes, leaving it empty", cartao.nome, campo, maximo);
+		}
+		escolhido.unwrap_or_default()
+	};
+	let telefone = primeiro_que_cabe(&cartao.telefones, max_telefone, "phone");
+	let email = primeiro_que_cabe(&cartao.emails, max_email, "email");
+	let notas = cartao.nota.filter(|nota| !nota.is_empty()).filter(|nota| {
+		let cabe = nota.len() <= max_notas;
+		if !cabe {
+			eprintln!("`{}`: note is longer than {} bytes, leaving it out", cartao.nome, max_notas);
+		}
+		cabe
+	});
+
+	let (ano_hoje, mes_hoje, dia_hoje) = hoje;
+	let (data_aniversario, idade) = match cartao.aniversario {
+		Some((Some(ano), _, _)) if ano < 1970 => return Err(format!("birthday in {} is before 1970", ano)),
+		Some((Some(ano), mes, dia)) => {
+			let fez_aniversario = (mes_hoje, dia_hoje) >= (mes, dia);
+			let idade = ano_hoje - ano - if fez_aniversario { 0 } else { 1 };
+			(data_texto((ano, mes, dia)), idade.max(0) as u32)
+		},
+		Some((None, _, _)) => return Err("birthday has no year".into()),
+		None => return Err("no birthday (BDAY)".into()),
+	};
+
+	Ok(NovoContato {
+		nome: cartao.nome,
+		telefone,
+		email,
+		idade,
+		data_aniversario,
+		categoria: categoria(&cartao.categorias),
+		notas,
+	})
+}
+
+// The first category that names one of the pallet's, in English or Portuguese
+fn categoria(categorias: &[String]) -> Categoria {
+	categorias
+		.iter()
+		.find_map(|categoria| match busca::normalizar(categoria.as_bytes()).as_str() {
+			"friend" | "friends" | "amigo" | "amigos" | "amiga" | "amigas" => Some(Categoria::Amigo),
+			"family" | "familia" | "familiar" => Some(Categoria::Familiar),
+			"work" | "colleague" | "colleagues" | "coworker" | "colega" | "colegas" | "trabalho" =>
+				Some(Categoria::Colega),
+			_ => None,
+		})
+		.unwrap_or(Categoria::Outro)
+}
+
+fn cartao_de_contato(contato: Contato<Runtime>) -> vcard::Cartao {
+	let texto = |bytes: &[u8]| String::from_utf8_lossy(bytes).trim().to_string();
+	let (ano, mes, dia) = data_civil(contato.data_aniversario);
+	let categoria = match contato.categoria {
+		Categoria::Amigo => Some("Friend"),
+		Categoria::Familiar => Some("Family"),
+		Categoria::Colega => Some("Colleague"),
+		Categoria::Outro => None,
+	};
+	vcard::Cartao {
+		nome: texto(&contato.nome),
+		telefones: Some(texto(&contato.telefone)).filter(|telefone| !telefone.is_empty()).into_iter().collect(),
+		emails: Some(texto(&contato.email)).filter(|email| !email.is_empty()).into_iter().collect(),
+		aniversario: Some((Some(ano), mes, dia)),
+		categorias: categoria.map(str::to_string).into_iter().collect(),
+		nota: Some(texto(&contato.notas)).filter(|nota| !nota.is_empty()),
+	}
+}
+
+// RFC 5545 reads 1-4 as high, 5 as medium and 6-9 as low; an undefined priority counts as medium
+fn prioridade_do_evento(prioridade: u8) -> Prioridade {
+	match prioridade {
+		1..=4 => Prioridade::Alta,
+		6..=9 => Prioridade::Baixa,
+		_ => Prioridade::Media,
+	}
+}
+
+// Titles are cut at the runtime limit rather than skipping the event
+fn titulo_do_evento(titulo: &str) -> String {
+	let maximo = limite::<<Runtime as gcc1919_agenda_pallets::Config>::MaxTituloLength>();
+	if titulo.len() <= maximo {
+		return titulo.to_string();
+	}
+	let corte = (0..=maximo).rev().find(|corte| titulo.is_char_boundary(*corte)).unwrap_or(0);
+	eprintln!("`{}`: title cut to {} bytes", titulo, maximo);
+	titulo[..corte].trim_end().to_string()
+}
+
+fn evento_de_compromisso(compromisso: Compromisso<Runtime>, conta: &str) -> ical::Evento {
+	let texto = |bytes: &[u8]| String::from_utf8_lossy(bytes).trim().to_string();
+	let quando = if compromisso.dia_inteiro {
+		ical::Quando::DiaInteiro { inicio: data_civil(compromisso.data), fim: data_civil(compromisso.data_fim) }
+	} else {
+		let minuto = texto(&compromisso.hora)
+			.split_once(':')
+			.and_then(|(horas, minutos)| Some(horas.parse::<u32>().ok()? * 60 + minutos.parse::<u32>().ok()?))
+			.unwrap_or(0);
+		ical::Quando::Horario { data: data_civil(compromisso.data), minuto, duracao: compromisso.duracao }
+	};
+	// Fixed point with six decimal places, as stored by the pallet
+	let graus = |valor: i32| {
+		let sinal = if valor < 0 { "-" } else { "" };
+		format!("{}{}.{:06}", sinal, valor.unsigned_abs() / 1_000_000, valor.unsigned_abs() % 1_000_000)
+	};
+	let (local, geo) = match &compromisso.local {
+		Some(Local::Endereco(endereco)) => (Some(texto(endereco)), None),
+		Some(Local::Coordenadas { latitude, longitude }) =>
+			(None, Some(format!("{};{}", graus(*latitude), graus(*longitude)))),
+		None => (None, None),
+	};
+	let status = match compromisso.status {
+		Status::Agendado => "TENTATIVE",
+		Status::Confirmado | Status::Concluido => "CONFIRMED",
+		Status::Cancelado => "CANCELLED",
+	};
+
+	ical::Evento {
+		uid: Some(format!("{}-{}@gcc1919-agenda", compromisso.id, conta)),
+		titulo: texto(&compromisso.titulo),
+		quando,
+		prioridade: match compromisso.prioridade {
+			Prioridade::Alta => 1,
+			Prioridade::Media => 5,
+			Prioridade::Baixa => 9,
+		},
+		status: Some(status.to_string()),
+		descricao: Some(texto(&compromisso.notas)).filter(|notas| !notas.is_empty()),
+		local,
+		geo,
+		url: compromisso.link.as_ref().map(|link| texto(link)),
+		recorrencia: None,
+		excecoes: Vec::new(),
+	}
+}
+
+/// A calendar date as (year, month, day).
+pub type Data = (i32, u32, u32);
+
+// `dd/mm/yyyy`, the format the pallet's calls take
+fn data_texto((ano, mes, dia): Data) -> String {
+	format!("{:02}/{:02}/{:04}", dia, mes, ano)
+}
+
+/// Civil date in UTC of a Unix timestamp in seconds.
+pub fn data_civil(timestamp: u64) -> Data {
+	data_do_dia((timestamp / 86_400) as i64)
+}
+
+/// Civil date of a day counted from 1 January 1970.
+pub fn data_do_dia(dia: i64) -> Data {
+	// Howard Hinnant's days-to-civil, with eras of 400 years starting on 1 March
+	let dias = dia + 719_468;
+	let era = dias.div_euclid(146_097);
+	let dia_da_era = dias.rem_euclid(146_097);
+	let ano_da_era = (dia_da_era - dia_da_era / 1_460 + dia_da_era / 36_524 - dia_da_era / 146_096) / 365;
+	let dia_do_ano = dia_da_era - (365 * ano_da_era + ano_da_era / 4 - ano_da_era / 100);
+	let mes_deslocado = (5 * dia_do_ano + 2) / 153;
+	let dia = dia_do_ano - (153 * mes_deslocado + 2) / 5 + 1;
+	let mes = if mes_deslocado < 10 { mes_deslocado + 3 } else { mes_deslocado - 9 };
+	let ano = ano_da_era + era * 400 + if mes <= 2 { 1 } else { 0 };
+	(ano as i32, mes as u32, dia as u32)
+}
+
+/// Days from 1 January 1970 to `data`; the inverse of [`data_do_dia`].
+pub fn dia_da_data((ano, mes, dia): Data) -> i64 {
+	let ano = ano as i64 - if mes <= 2 { 1 } else { 0 };
+	let era = ano.div_euclid(400);
+	let ano_da_era = ano.rem_euclid(400);
+	let mes_deslocado = if mes > 2 { mes as i64 - 3 } else { mes as i64 + 9 };
+	let dia_do_ano = (153 * mes_deslocado + 2) / 5 + dia as i64 - 1;
+	let dia_da_era = ano_da_era * 365 + ano_da_era / 4 - ano_da_era / 100 + dia_do_ano;
+	era * 146_097 + dia_da_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn cartao(aniversario: Option<(Option<i32>, u32, u32)>) -> vcard::Cartao {
+		vcard::Cartao { nome: "Ana".into(), aniversario, ..Default::default() }
+	}
+
+	#[test]
+	fn contacts_need_a_full_birthday() {
+		let hoje = (2025, 3, 10);
+		let novo = novo_contato(cartao(Some((Some(1990), 5, 17))), hoje).unwrap();
+		assert_eq!((novo.data_aniversario.as_str(), novo.idade), ("17/05/1990", 34));
+
+		assert!(novo_contato(cartao(None), hoje).is_err());
+		assert!(novo_contato(cartao(Some((None, 5, 17))), hoje).is_err());
+		assert!(novo_contato(cartao(Some((Some(1969), 5, 17))), hoje).is_err());
+	}
+}
@@ -0,0 +1,181 @@
+//! Content lines shared by vCard (RFC 6350) and iCalendar (RFC 5545): folding, parameters and
+//! text escaping work the same way in both formats.
+
+/// A content line split into its name, parameters and raw value.
+pub struct Propriedade {
+	/// Property name in upper case, without any group prefix.
+	pub nome: String,
+	/// Parameter names in upper case with their unquoted values.
+	pub parametros: Vec<(String, String)>,
+	/// The value exactly as written, still escaped.
+	pub valor: String,
+}
+
+impl Propriedade {
+	/// Value of the parameter `nome`, if present.
+	pub fn parametro(&self, nome: &str) -> Option<&str> {
+		self.parametros.iter().find(|(parametro, _)| parametro == nome).map(|(_, valor)| valor.as_str())
+	}
+}
+
+// Continuation lines start with a space or tab and are glued onto the previous line
+pub fn desdobrar(texto: &str) -> Vec<String> {
+	let mut linhas: Vec<String> = Vec::new();
+	for linha in texto.split('\n') {
+		let linha = linha.strip_suffix('\r').unwrap_or(linha);
+		if let Some(continuacao) = linha.strip_prefix(' ').or_else(|| linha.strip_prefix('\t')) {
+			if let Some(anterior) = linhas.last_mut() {
+				anterior.push_str(continuacao);
+				continue;
+			}
+		}
+		if !linha.trim().is_empty() {
+			linhas.push(linha.to_string());
+		}
+	}
+	linhas
+}
+
+pub fn dobrar(linha: &str, saida: &mut String) {
+	let mut tamanho = 0;
+	for c in linha.chars() {
+		if tamanho + c.len_utf8() > 75 {
+			saida.push_str("\r\n ");
+			tamanho = 1;
+		}
+		saida.push(c);
+		tamanho += c.len_utf8();
+	}
+	saida.push_str("\r\n");
+}
+
+// `group.NAME;PARAM=value;TYPE=a,b:value`, with quoted parameter values allowed to contain ':'
+pub fn propriedade(linha: &str) -> Option<Propriedade> {
+	let mut aspas = false;
+	let (posicao, _) = linha.char_indices().find(|(_, c)| {
+		if *c == '"' {
+			aspas = !aspas;
+		}
+		*c == ':' && !aspas
+	})?;
+	let mut partes = linha[..posicao].split(';');
+	let nome = partes.next()?.rsplit('.').next()?.trim().to_ascii_uppercase();
+	let parametros = partes
+		.map(|parametro| match parametro.split_once('=') {
+			Some((nome, valor)) => (nome.trim().to_ascii_uppercase(), valor.trim_matches('"').to_string()),
+			// Bare types as written by older exporters, e.g. `TEL;CELL:`
+			None => ("TYPE".to_string(), parametro.to_string()),
+		})
+		.collect();
+	Some(Propriedade { nome, parametros, valor: linha[posicao + 1..].to_string() })
+}
+
+pub fn desescapar(valor: &str) -> String {
+	let mut saida = String::with_capacity(valor.len());
+	let mut caracteres = valor.chars();
+	while let Some(c) = caracteres.next() {
+		if c != '\\' {
+			saida.push(c);
+			continue;
+		}
+		match caracteres.next() {
+			Some('n' | 'N') => saida.push('\n'),
+			Some(outro) => saida.push(outro),
+			None => saida.push('\\'),
+		}
+	}
+	saida
+}
+
+pub fn escapar(valor: &str) -> String {
+	let mut saida = String::with_capacity(valor.len());
+	for c in valor.chars() {
+		match c {
+			'\\' | ',' | ';' => {
+				saida.push('\\');
+				saida.push(c);
+			},
+			'\n' => saida.push_str("\\n"),
+			'\r' => {},
+			_ => saida.push(c),
+		}
+	}
+	saida
+}
+
+// Splits on unescaped `separador` and unescapes each part
+pub fn dividir(valor: &str, separador: char) -> Vec<String> {
+	let mut partes = Vec::new();
+	let mut atual = String::new();
+	let mut caracteres = valor.chars();
+	while let Some(c) = caracteres.next() {
+		if c == '\\' {
+			atual.push(c);
+			if let Some(escapado) = caracteres.next() {
+				atual.push(escapado);
+			}
+		} else if c == separador {
+			partes.push(desescapar(&atual));
+			atual.clear();
+		} else {
+			atual.push(c);
+		}
+	}
+	partes.push(desescapar(&atual));
+	partes
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn unfolds_continuation_lines() {
+		let linhas = desdobrar("BEGIN:VCARD\r\nNOTE:um\r\n  dois\r\n\ttr\u{ea}s\r\n\r\nEND:VCARD\r\n");
+		assert_eq!(linhas, vec!["BEGIN:VCARD", "NOTE:um doistr\u{ea}s", "END:VCARD"]);
+		// A continuation with nothing before it is kept as a line of its own
+		assert_eq!(desdobrar(" FN:Ana\n"), vec![" FN:Ana"]);
+	}
+
+	#[test]
+	fn folds_at_75_octets_without_splitting_characters() {
+		let linha = format!("NOTE:{}", "a\u{e7}\u{e3}o ".repeat(30));
+		let mut saida = String::new();
+		dobrar(&linha, &mut saida);
+		assert!(saida.ends_with("\r\n"));
+		assert!(saida.split("\r\n").all(|parte| parte.len() <= 75));
+		assert!(saida.split("\r\n").skip(1).filter(|parte| !parte.is_empty()).all(|parte| parte.starts_with(' ')));
+		assert_eq!(desdobrar(&saida), vec![linha]);
+	}
+
+	#[test]
+	fn splits_name_parameters_and_value() {
+		let linha = propriedade("item1.tel;type=CELL,pref;X-LABEL=\"Casa: fundos\":+55 11 1234").unwrap();
+		assert_eq!(linha.nome, "TEL");
+		assert_eq!(
+			linha.parametros,
+			vec![
+				("TYPE".to_string(), "CELL,pref".to_string()),
+				("X-LABEL".to_string(), "Casa: fundos".to_string())
+			]
+		);
+		assert_eq!(linha.parametro("X-LABEL"), Some("Casa: fundos"));
+		assert_eq!(linha.valor, "+55 11 1234");
+
+		let antiga = propriedade("TEL;CELL:123").unwrap();
+		assert_eq!(antiga.parametro("TYPE"), Some("CELL"));
+		assert!(propriedade("sem dois pontos").is_none());
+	}
+
+	#[test]
+	fn escapes_and_unescapes_text() {
+		let texto = "a,b;c\\d\nfim";
+		assert_eq!(escapar(texto), "a\\,b\\;c\\\\d\\nfim");
+		assert_eq!(desescapar(&escapar(texto)), texto);
+		assert_eq!(escapar("linha\r\n"), "linha\\n");
+		assert_eq!(desescapar("A\\NB\\"), "A\nB\\");
+		assert_eq!(dividir("Souza;Ana\\;Maria;;", ';'), vec!["Souza", "Ana;Maria", "", ""]);
+		assert_eq!(dividir("Work\\, old,Friends", ','), vec!["Work, old", "Friends"]);
+	}
+}
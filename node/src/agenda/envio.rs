@@ -0,0 +1,173 @@
+//! Getting agenda calls onto a chain: signed and submitted to a running node, or written to a
+//! file as SCALE-encoded calls for another tool to wrap.
+
+use std::{fs, path::Path};
+
+use codec::{Decode, Encode};
+use gcc1919_agenda_pallets::{Escritas, Pallet as Agenda};
+use jsonrpsee::{
+	core::{client::ClientT, ClientError},
+	rpc_params,
+	ws_client::{WsClient, WsClientBuilder},
+};
+use solochain_template_runtime as runtime;
+use sp_core::{crypto::Ss58Codec, hexdisplay::HexDisplay, sr25519, Bytes, Get, Pair, H256};
+use sp_runtime::AccountId32;
+
+/// Writes each call as a `0x`-prefixed hex line of its SCALE encoding.
+pub fn gravar_chamadas(caminho: &Path, chamadas: &[runtime::RuntimeCall]) -> std::io::Result<()> {
+	let linhas: String =
+		chamadas.iter().map(|chamada| format!("0x{}\n", HexDisplay::from(&chamada.encode()))).collect();
+	fs::write(caminho, linhas)
+}
+
+/// Signs `chamadas` with consecutive nonces and submits them to the node at `url`, returning the
+/// extrinsic hashes in order.
+///
+/// The node must run the runtime this binary was built with, since the signed payload commits to
+/// its spec and transaction versions. Nothing is submitted when the calls need more writes than
+/// the account has left in its current quota window.
+pub async fn enviar(
+	url: &str,
+	par: &sr25519::Pair,
+	chamadas: Vec<runtime::RuntimeCall>,
+) -> Result<Vec<H256>, String> {
+	let erro = |e: ClientError| format!("{}: {}", url, e);
+	let cliente = WsClientBuilder::default().build(url).await.map_err(erro)?;
+
+	let versao: serde_json::Value =
+		cliente.request("state_getRuntimeVersion", rpc_params![]).await.map_err(erro)?;
+	let spec_version = versao["specVersion"].as_u64();
+	let transaction_version = versao["transactionVersion"].as_u64();
+	if spec_version != Some(runtime::VERSION.spec_version.into()) ||
+		transaction_version != Some(runtime::VERSION.transaction_version.into())
+	{
+		return Err(format!(
+			"{} runs spec version {:?} / transaction version {:?}, this binary was built for {} / {}",
+			url,
+			spec_version,
+			transaction_version,
+			runtime::VERSION.spec_version,
+			runtime::VERSION.transaction_version,
+		));
+	}
+
+	let genesis_hash: Option<H256> =
+		cliente.request("chain_getBlockHash", rpc_params![0u32]).await.map_err(erro)?;
+	let genesis_hash = genesis_hash.ok_or_else(|| format!("{}: node has no genesis block", url))?;
+	let conta = AccountId32::from(par.public());
+	let mut nonce: u32 =
+		cliente.request("system_accountNextIndex", rpc_params![conta.to_ss58check()]).await.map_err(erro)?;
+
+	let necessarias: u32 = chamadas.iter().map(escritas).sum();
+	let maximo: u32 = <runtime::Runtime as gcc1919_agenda_pallets::Config>::MaxEscritasPorJanela::get();
+	let janela: runtime::BlockNumber = <runtime::Runtime as gcc1919_agenda_pallets::Config>::JanelaEscritas::get();
+	if necessarias > maximo {
+		return Err(format!(
+			"these calls need {} writes, more than the {} an account may make every {} blocks; \
+			 split the input into smaller files",
+			necessarias, maximo, janela,
+		));
+	}
+	let (restantes, fim_da_janela) = cota_restante(&cliente, &conta).await.map_err(erro)?;
+	if necessarias > restantes {
+		return Err(format!(
+			"these calls need {} writes but {} has only {} left in its quota until block {}",
+			necessarias, conta, restantes, fim_da_janela,
+		));
+	}
+
+	let mut hashes = Vec::with_capacity(chamadas.len());
+	for chamada in chamadas {
+		let extrinsic = assinar(par, chamada, nonce, genesis_hash);
+		let hash: H256 = cliente
+			.request("author_submitExtrinsic", rpc_params![Bytes(extrinsic.encode())])
+			.await
+			.map_err(erro)?;
+		hashes.push(hash);
+		nonce += 1;
+	}
+	Ok(hashes)
+}
+
+// Writes a call takes from the account's quota, counted the way the pallet's `VerificarCota` does
+fn escritas(chamada: &runtime::RuntimeCall) -> u32 {
+	match chamada {
+		runtime::RuntimeCall::GCC1919AgendaPallet(chamada) => Agenda::<runtime::Runtime>::escritas_da_chamada(chamada),
+		_ => 0,
+	}
+}
+
+// Writes the account can still make in its quota window as of the next block, and the block that
+// window ends at
+async fn cota_restante(cliente: &WsClient, conta: &AccountId32) -> Result<(u32, u32), ClientError> {
+	let maximo: u32 = <runtime::Runtime as gcc1919_agenda_pallets::Config>::MaxEscritasPorJanela::get();
+	let janela: runtime::BlockNumber = <runtime::Runtime as gcc1919_agenda_pallets::Config>::JanelaEscritas::get();
+
+	let chave = Escritas::<runtime::Runtime>::hashed_key_for(conta);
+	let valor: Option<Bytes> = cliente.request("state_getStorage", rpc_params![Bytes(chave)]).await?;
+	let (inicio, usadas) = match valor {
+		Some(valor) => <(runtime::BlockNumber, u32)>::decode(&mut &valor[..])
+			.map_err(|e| ClientError::Custom(format!("decoding the write quota: {}", e)))?,
+		None => (0, 0),
+	};
+	let cabecalho: Option<runtime::Header> = cliente.request("chain_getHeader", rpc_params![]).await?;
+	let cabecalho = cabecalho.ok_or_else(|| ClientError::Custom("node has no best block".to_string()))?;
+
+	// The calls are included in the next block at the earliest
+	let proximo = cabecalho.number + 1;
+	if proximo >= inicio.saturating_add(janela) {
+		Ok((maximo, proximo + janela))
+	} else {
+		Ok((maximo.saturating_sub(usadas), inicio + janela))
+	}
+}
+
+// Same extensions as `benchmarking::create_benchmark_extrinsic`, but immortal, so the payload
+// only commits to the genesis hash. A transaction over the account's write quota is dropped
+// from the pool rather than waiting for the next window, which is why `enviar` checks the
+// quota before signing anything.
+fn assinar(
+	par: &sr25519::Pair,
+	chamada: runtime::RuntimeCall,
+	nonce: u32,
+	genesis_hash: H256,
+) -> runtime::UncheckedExtrinsic {
+	let extra: runtime::SignedExtra = (
+		frame_system::CheckNonZeroSender::<runtime::Runtime>::new(),
+		frame_system::CheckSpecVersion::<runtime::Runtime>::new(),
+		frame_system::CheckTxVersion::<runtime::Runtime>::new(),
+		frame_system::CheckGenesis::<runtime::Runtime>::new(),
+		frame_system::CheckEra::<runtime::Runtime>::from(sp_runtime::generic::Era::Immortal),
+		frame_system::CheckNonce::<runtime::Runtime>::from(nonce),
+		frame_system::CheckWeight::<runtime::Runtime>::new(),
+		pallet_transaction_payment::ChargeTransactionPayment::<runtime::Runtime>::from(0),
+		frame_metadata_hash_extension::CheckMetadataHash::<runtime::Runtime>::new(false),
+		gcc1919_agenda_pallets::extensao::VerificarCota::<runtime::Runtime>::new(),
+	);
+
+	let raw_payload = runtime::SignedPayload::from_raw(
+		chamada.clone(),
+		extra.clone(),
+		(
+			(),
+			runtime::VERSION.spec_version,
+			runtime::VERSION.transaction_version,
+			genesis_hash,
+			genesis_hash,
+			(),
+			(),
+			(),
+			None,
+			(),
+		),
+	);
+	let assinatura = raw_payload.using_encoded(|payload| par.sign(payload));
+
+	runtime::UncheckedExtrinsic::new_signed(
+		chamada,
+		AccountId32::from(par.public()).into(),
+		runtime::Signature::Sr25519(assinatura),
+		extra,
+	)
+}
@@ -0,0 +1,689 @@
+//! A small iCalendar (RFC 5545) reader and writer for `VEVENT`s, covering what an agenda
+//! appointment can hold.
+//!
+//! Times are taken as written: a `DTSTART` with `TZID` or in UTC keeps its wall-clock value, the
+//! same way the pallet stores `hora` without a time zone. Other components (`VTODO`,
+//! `VTIMEZONE`, alarms inside events) are skipped.
+
+use super::{
+	conteudo::{desdobrar, desescapar, dobrar, escapar, propriedade, Propriedade},
+	data_do_dia, dia_da_data, Data,
+};
+
+/// When an event happens.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Quando {
+	/// Starts on `data` at `minuto` minutes after midnight and lasts `duracao` minutes.
+	Horario { data: Data, minuto: u32, duracao: u32 },
+	/// Covers every day from `inicio` to `fim`, both inclusive.
+	DiaInteiro { inicio: Data, fim: Data },
+}
+
+/// Recurrence frequencies the agenda can expand.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Frequencia {
+	/// `FREQ=DAILY`.
+	Diaria,
+	/// `FREQ=WEEKLY`, optionally with `BYDAY`.
+	Semanal,
+	/// `FREQ=MONTHLY` on the start's day of the month.
+	Mensal,
+	/// `FREQ=YEARLY` on the start's month and day.
+	Anual,
+}
+
+/// The supported subset of an `RRULE`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Regra {
+	/// How often the event repeats.
+	pub frequencia: Frequencia,
+	/// Every how many periods, at least 1.
+	pub intervalo: u32,
+	/// Total number of occurrences, including the first.
+	pub contagem: Option<u32>,
+	/// Last date an occurrence may fall on.
+	pub ate: Option<Data>,
+	/// Weekdays for weekly rules, 0 for Monday to 6 for Sunday.
+	pub dias_semana: Vec<u32>,
+}
+
+/// An event read from or written to a calendar.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Evento {
+	/// Globally unique identifier.
+	pub uid: Option<String>,
+	/// `SUMMARY`.
+	pub titulo: String,
+	/// `DTSTART` with `DTEND` or `DURATION`.
+	pub quando: Quando,
+	/// `PRIORITY`, 1 (highest) to 9 (lowest), 0 when undefined.
+	pub prioridade: u8,
+	/// `STATUS`, e.g. `CONFIRMED` or `CANCELLED`.
+	pub status: Option<String>,
+	/// `DESCRIPTION`.
+	pub descricao: Option<String>,
+	/// `LOCATION`.
+	pub local: Option<String>,
+	/// `GEO` as written, `latitude;longitude` in degrees.
+	pub geo: Option<String>,
+	/// `URL`.
+	pub url: Option<String>,
+	/// `RRULE`, or the raw rule text when it uses parts the agenda can't expand.
+	pub recorrencia: Option<Result<Regra, String>>,
+	/// `EXDATE` dates to leave out of the recurrence.
+	pub excecoes: Vec<Data>,
+}
+
+#[derive(Default)]
+struct Rascunho {
+	uid: Option<String>,
+	titulo: Option<String>,
+	inicio: Option<(Data, Option<u32>)>,
+	fim: Option<(Data, Option<u32>)>,
+	duracao: Option<i64>,
+	prioridade: u8,
+	status: Option<String>,
+	descricao: Option<String>,
+	local: Option<String>,
+	geo: Option<String>,
+	url: Option<String>,
+	recorrencia: Option<Result<Regra, String>>,
+	excecoes: Vec<Data>,
+}
+
+/// Reads every `VEVENT` in `texto`.
+pub fn ler(texto: &str) -> Result<Vec<Evento>, String> {
+	let mut eventos = Vec::new();
+	let mut atual: Option<Rascunho> = None;
+	// Components nested inside the current event, such as VALARM
+	let mut aninhados = 0u32;
+
+	for (indice, linha) in desdobrar(texto).iter().enumerate() {
+		let Some(propriedade) = propriedade(linha) else {
+			return Err(format!("line {}: expected `NAME:value`, found `{}`", indice + 1, linha));
+		};
+		let componente = propriedade.valor.trim().to_ascii_uppercase();
+		match propriedade.nome.as_str() {
+			"BEGIN" if atual.is_none() =>
+				if componente == "VEVENT" {
+					atual = Some(Rascunho::default());
+				},
+			"BEGIN" => aninhados += 1,
+			"END" if atual.is_some() && aninhados > 0 => aninhados -= 1,
+			"END" if atual.is_some() && componente == "VEVENT" => {
+				let rascunho = atual.take().expect("checked by the guard above; qed");
+				eventos.push(
+					evento(rascunho).map_err(|e| format!("event ending on line {}: {}", indice + 1, e))?,
+				);
+			},
+			_ =>
+				if let Some(rascunho) = atual.as_mut().filter(|_| aninhados == 0) {
+					interpretar(&propriedade, rascunho).map_err(|e| format!("line {}: {}", indice + 1, e))?;
+				},
+		}
+	}
+
+	match atual {
+		Some(_) => Err("last VEVENT is missing END:VEVENT".to_string()),
+		None => Ok(eventos),
+	}
+}
+
+fn interpretar(propriedade: &Propriedade, rascunho: &mut Rascunho) -> Result<(), String> {
+	let texto = || desescapar(&propriedade.valor).trim().to_string();
+	match propriedade.nome.as_str() {
+		"UID" => rascunho.uid = Some(texto()),
+		"SUMMARY" => rascunho.titulo = Some(texto()),
+		"DTSTART" => rascunho.inicio = Some(data_hora(&propriedade.valor)?),
+		"DTEND" => rascunho.fim = Some(data_hora(&propriedade.valor)?),
+		"DURATION" => rascunho.duracao = Some(duracao(&propriedade.valor)?),
+		"PRIORITY" =>
+			rascunho.prioridade = propriedade
+				.valor
+				.trim()
+				.parse()
+				.ok()
+				.filter(|prioridade| *prioridade <= 9)
+				.ok_or_else(|| format!("invalid PRIORITY `{}`", propriedade.valor))?,
+		"STATUS" => rascunho.status = Some(texto().to_ascii_uppercase()),
+		"DESCRIPTION" => rascunho.descricao = Some(desescapar(&propriedade.valor)),
+		"LOCATION" => rascunho.local = Some(texto()),
+		"GEO" => rascunho.geo = Some(propriedade.valor.trim().to_string()),
+		"URL" => rascunho.url = Some(propriedade.valor.trim().to_string()),
+		"RRULE" => rascunho.recorrencia = Some(regra(&propriedade.valor)),
+		"EXDATE" =>
+			for valor in propriedade.valor.split(',') {
+				rascunho.excecoes.push(data_hora(valor)?.0);
+			},
+		_ => {},
+	}
+	Ok(())
+}
+
+fn evento(rascunho: Rascunho) -> Result<Evento, String> {
+	let (inicio, minuto) = rascunho.inicio.ok_or("missing DTSTART")?;
+	let quando = match minuto {
+		Some(minuto) => {
+			let duracao = match (rascunho.fim, rascunho.duracao) {
+				(Some((fim, minuto_fim)), _) =>
+					(dia_da_data(fim) - dia_da_data(inicio)) * 24 * 60 + minuto_fim.unwrap_or(0) as i64 -
+						minuto as i64,
+				(None, Some(duracao)) => duracao,
+				// RFC 5545: a timed event without an end ends when it starts
+				(None, None) => 0,
+			};
+			let duracao = u32::try_from(duracao).map_err(|_| "event ends before it starts")?;
+			Quando::Horario { data: inicio, minuto, duracao }
+		},
+		None => {
+			// DTEND of an all-day event is exclusive; without an end it takes the start day
+			let dias = match (rascunho.fim, rascunho.duracao) {
+				(Some((fim, _)), _) => dia_da_data(fim) - dia_da_data(inicio),
+				(None, Some(duracao)) => duracao / (24 * 60),
+				(None, None) => 1,
+			};
+			if dias < 1 {
+				return Err("event ends before it starts".to_string());
+			}
+			Quando::DiaInteiro { inicio, fim: data_do_dia(dia_da_data(inicio) + dias - 1) }
+		},
+	};
+
+	Ok(Evento {
+		uid: rascunho.uid,
+		titulo: rascunho.titulo.unwrap_or_default(),
+		quando,
+		prioridade: rascunho.prioridade,
+		status: rascunho.status,
+		descricao: rascunho.descricao,
+		local: rascunho.local,
+		geo: rascunho.geo,
+		url: rascunho.url,
+		recorrencia: rascunho.recorrencia,
+		excecoes: rascunho.excecoes,
+	})
+}
+
+/// Writes `eventos` as one `VCALENDAR`, stamping each with `carimbo` (`YYYYMMDDTHHMMSSZ`).
+pub fn escrever(eventos: &[Evento], carimbo: &str) -> String {
+	let mut linhas = vec![
+		"BEGIN:VCALENDAR".to_string(),
+		"VERSION:2.0".to_string(),
+		"PRODID:-//GCC1919//Agenda//PT".to_string(),
+		"CALSCALE:GREGORIAN".to_string(),
+	];
+	for evento in eventos {
+		linhas.push("BEGIN:VEVENT".to_string());
+		if let Some(uid) = &evento.uid {
+			linhas.push(format!("UID:{}", escapar(uid)));
+		}
+		linhas.push(format!("DTSTAMP:{}", carimbo));
+		match evento.quando {
+			Quando::Horario { data, minuto, duracao } => {
+				linhas.push(format!("DTSTART:{}T{:02}{:02}00", data_compacta(data), minuto / 60, minuto % 60));
+				linhas.push(format!("DURATION:{}", duracao_iso(duracao)));
+			},
+			Quando::DiaInteiro { inicio, fim } => {
+				linhas.push(format!("DTSTART;VALUE=DATE:{}", data_compacta(inicio)));
+				let fim_exclusivo = data_do_dia(dia_da_data(fim) + 1);
+				linhas.push(format!("DTEND;VALUE=DATE:{}", data_compacta(fim_exclusivo)));
+			},
+		}
+		linhas.push(format!("SUMMARY:{}", escapar(&evento.titulo)));
+		if evento.prioridade != 0 {
+			linhas.push(format!("PRIORITY:{}", evento.prioridade));
+		}
+		let opcionais = [
+			("STATUS", &evento.status),
+			("DESCRIPTION", &evento.descricao),
+			("LOCATION", &evento.local),
+		];
+		for (nome, valor) in opcionais {
+			if let Some(valor) = valor.as_ref().filter(|valor| !valor.is_empty()) {
+				linhas.push(format!("{}:{}", nome, escapar(valor)));
+			}
+		}
+		// GEO and URL values are not TEXT, so they are written unescaped
+		for (nome, valor) in [("GEO", &evento.geo), ("URL", &evento.url)] {
+			if let Some(valor) = valor {
+				linhas.push(format!("{}:{}", nome, valor));
+			}
+		}
+		if let Some(Ok(regra)) = &evento.recorrencia {
+			linhas.push(format!("RRULE:{}", regra_texto(regra)));
+		}
+		if !evento.excecoes.is_empty() {
+			let datas = evento.excecoes.iter().map(|data| data_compacta(*data)).collect::<Vec<_>>().join(",");
+			linhas.push(format!("EXDATE;VALUE=DATE:{}", datas));
+		}
+		linhas.push("END:VEVENT".to_string());
+	}
+	linhas.push("END:VCALENDAR".to_string());
+
+	let mut saida = String::new();
+	for linha in linhas {
+		dobrar(&linha, &mut saida);
+	}
+	saida
+}
+
+/// The occurrences of an event that fall in the range being imported.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Ocorrencias {
+	/// Start dates, in order.
+	pub datas: Vec<Data>,
+	/// Occurrences left out for starting before the range.
+	pub anteriores: usize,
+	/// Set when the recurrence had more occurrences than asked for, including open-ended rules.
+	pub truncado: bool,
+}
+
+/// Start dates of the event's occurrences from `desde` on, at most `maximo` of them, leaving out
+/// `EXDATE`s. Occurrences before `desde` don't count towards `maximo`. An unsupported rule yields
+/// only the first occurrence.
+pub fn ocorrencias(evento: &Evento, desde: Data, maximo: usize) -> Ocorrencias {
+	let inicio = match evento.quando {
+		Quando::Horario { data, .. } => data,
+		Quando::DiaInteiro { inicio, .. } => inicio,
+	};
+	let mut ocorrencias = Ocorrencias::default();
+	let Some(Ok(regra)) = &evento.recorrencia else {
+		if !evento.excecoes.contains(&inicio) {
+			if inicio < desde {
+				ocorrencias.anteriores = 1;
+			} else {
+				ocorrencias.datas.push(inicio);
+			}
+		}
+		return ocorrencias;
+	};
+
+	let primeiro_dia = dia_da_data(inicio);
+	let intervalo = regra.intervalo.max(1) as i64;
+	let limite = regra.ate.map(dia_da_data);
+	let mut geradas = 0u32;
+	// Candidate dates in order; a `None` period means the date doesn't exist (e.g. 31 April)
+	for periodo in 0i64.. {
+		let candidatas: Vec<Data> = match regra.frequencia {
+			Frequencia::Diaria => Vec::from([data_do_dia(primeiro_dia + periodo * intervalo)]),
+			Frequencia::Semanal if regra.dias_semana.is_empty() =>
+				Vec::from([data_do_dia(primeiro_dia + periodo * intervalo * 7)]),
+			Frequencia::Semanal => {
+				let segunda = primeiro_dia - dia_da_semana(primeiro_dia) as i64 + periodo * intervalo * 7;
+				let mut dias = regra.dias_semana.clone();
+				dias.sort();
+				dias.dedup();
+				dias.into_iter()
+					.map(|dia| segunda + dia as i64)
+					.filter(|dia| *dia >= primeiro_dia)
+					.map(data_do_dia)
+					.collect()
+			},
+			Frequencia::Mensal => {
+				let meses = (inicio.1 as i64 - 1) + periodo * intervalo;
+				let (ano, mes) = (inicio.0 + meses.div_euclid(12) as i32, meses.rem_euclid(12) as u32 + 1);
+				data_valida((ano, mes, inicio.2)).into_iter().collect()
+			},
+			Frequencia::Anual =>
+				data_valida((inicio.0 + (periodo * intervalo) as i32, inicio.1, inicio.2)).into_iter().collect(),
+		};
+
+		for data in candidatas {
+			if limite.is_some_and(|limite| dia_da_data(data) > limite) ||
+				regra.contagem.is_some_and(|contagem| geradas >= contagem)
+			{
+				return ocorrencias;
+			}
+			geradas += 1;
+			if evento.excecoes.contains(&data) {
+				continue;
+			}
+			if data < desde {
+				ocorrencias.anteriores += 1;
+				continue;
+			}
+			if ocorrencias.datas.len() == maximo {
+				ocorrencias.truncado = true;
+				return ocorrencias;
+			}
+			ocorrencias.datas.push(data);
+		}
+	}
+	unreachable!("the loop above only ends by returning")
+}
+
+fn data_valida(data: Data) -> Option<Data> {
+	(data_do_dia(dia_da_data(data)) == data).then_some(data)
+}
+
+// 0 for Monday; 1 January 1970 was a Thursday
+fn dia_da_semana(dia: i64) -> u32 {
+	(dia + 3).rem_euclid(7) as u32
+}
+
+const DIAS_SEMANA: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+fn regra(texto: &str) -> Result<Regra, String> {
+	let bruto = texto.trim().to_string();
+	let mut frequencia = None;
+	let mut regra =
+		Regra { frequencia: Frequencia::Diaria, intervalo: 1, contagem: None, ate: None, dias_semana: Vec::new() };
+	for parte in texto.trim().split(';') {
+		let (nome, valor) = parte.split_once('=').ok_or_else(|| bruto.clone())?;
+		match nome.to_ascii_uppercase().as_str() {
+			"FREQ" =>
+				frequencia = Some(match valor.to_ascii_uppercase().as_str() {
+					"DAILY" => Frequencia::Diaria,
+					"WEEKLY" => Frequencia::Semanal,
+					"MONTHLY" => Frequencia::Mensal,
+					"YEARLY" => Frequencia::Anual,
+					_ => return Err(bruto),
+				}),
+			"INTERVAL" => regra.intervalo = valor.parse().ok().filter(|n| *n > 0).ok_or_else(|| bruto.clone())?,
+			"COUNT" => regra.contagem = Some(valor.parse().map_err(|_| bruto.clone())?),
+			"UNTIL" => regra.ate = Some(data_hora(valor).map_err(|_| bruto.clone())?.0),
+			"BYDAY" =>
+				for dia in valor.split(',') {
+					let dia = DIAS_SEMANA
+						.iter()
+						.position(|nome| dia.eq_ignore_ascii_case(nome))
+						.ok_or_else(|| bruto.clone())?;
+					regra.dias_semana.push(dia as u32);
+				},
+			"WKST" => {},
+			_ => return Err(bruto),
+		}
+	}
+	regra.frequencia = frequencia.ok_or_else(|| bruto.clone())?;
+	if !regra.dias_semana.is_empty() && regra.frequencia != Frequencia::Semanal {
+		return Err(bruto);
+	}
+	Ok(regra)
+}
+
+fn regra_texto(regra: &Regra) -> String {
+	let mut partes = vec![format!(
+		"FREQ={}",
+		match regra.frequencia {
+			Frequencia::Diaria => "DAILY",
+			Frequencia::Semanal => "WEEKLY",
+			Frequencia::Mensal => "MONTHLY",
+			Frequencia::Anual => "YEARLY",
+		}
+	)];
+	if regra.intervalo > 1 {
+		partes.push(format!("INTERVAL={}", regra.intervalo));
+	}
+	if let Some(contagem) = regra.contagem {
+		partes.push(format!("COUNT={}", contagem));
+	}
+	if let Some(ate) = regra.ate {
+		partes.push(format!("UNTIL={}", data_compacta(ate)));
+	}
+	if !regra.dias_semana.is_empty() {
+		let dias = regra.dias_semana.iter().map(|dia| DIAS_SEMANA[*dia as usize]).collect::<Vec<_>>();
+		partes.push(format!("BYDAY={}", dias.join(",")));
+	}
+	partes.join(";")
+}
+
+fn data_compacta((ano, mes, dia): Data) -> String {
+	format!("{:04}{:02}{:02}", ano, mes, dia)
+}
+
+// `YYYYMMDD` or `YYYYMMDDTHHMMSS[Z]`, returning the minutes after midnight for the latter
+fn data_hora(valor: &str) -> Result<(Data, Option<u32>), String> {
+	let valor = valor.trim();
+	let invalida = || format!("invalid date `{}`", valor);
+	let (data, hora) = match valor.split_once('T') {
+		Some((data, hora)) => (data, Some(hora.trim_end_matches('Z'))),
+		None => (valor, None),
+	};
+	if data.len() != 8 || !data.chars().all(|c| c.is_ascii_digit()) {
+		return Err(invalida());
+	}
+	let data = (
+		data[..4].parse().map_err(|_| invalida())?,
+		data[4..6].parse().map_err(|_| invalida())?,
+		data[6..].parse().map_err(|_| invalida())?,
+	);
+	data_valida(data).ok_or_else(invalida)?;
+	let minuto = match hora {
+		Some(hora) => {
+			if hora.len() != 6 || !hora.chars().all(|c| c.is_ascii_digit()) {
+				return Err(invalida());
+			}
+			let (horas, minutos): (u32, u32) =
+				(hora[..2].parse().map_err(|_| invalida())?, hora[2..4].parse().map_err(|_| invalida())?);
+			if horas > 23 || minutos > 59 {
+				return Err(invalida());
+			}
+			Some(horas * 60 + minutos)
+		},
+		None => None,
+	};
+	Ok((data, minuto))
+}
+
+// `P1W`, `P1DT2H30M`, `PT45M`, ...; seconds are dropped
+fn duracao(valor: &str) -> Result<i64, String> {
+	let valor = valor.trim();
+	let invalida = || format!("invalid DURATION `{}`", valor);
+	let (sinal, resto) = match valor.strip_prefix('-') {
+		Some(resto) => (-1, resto),
+		None => (1, valor.strip_prefix('+').unwrap_or(valor)),
+	};
+	let resto = resto.strip_prefix('P').ok_or_else(invalida)?;
+	let mut minutos = 0i64;
+	let mut numero = String::new();
+	let mut na_hora = false;
+	for c in resto.chars() {
+		match c {
+			'0'..='9' => numero.push(c),
+			'T' if numero.is_empty() => na_hora = true,
+			_ => {
+				let n: i64 = numero.parse().map_err(|_| invalida())?;
+				numero.clear();
+				minutos += match (c, na_hora) {
+					('W', false) => n * 7 * 24 * 60,
+					('D', false) => n * 24 * 60,
+					('H', true) => n * 60,
+					('M', true) => n,
+					('S', true) => n / 60,
+					_ => return Err(invalida()),
+				};
+			},
+		}
+	}
+	if !numero.is_empty() {
+		return Err(invalida());
+	}
+	Ok(sinal * minutos)
+}
+
+fn duracao_iso(minutos: u32) -> String {
+	let (dias, horas, minutos) = (minutos / (24 * 60), minutos / 60 % 24, minutos % 60);
+	let mut texto = "P".to_string();
+	if dias > 0 {
+		texto.push_str(&format!("{}D", dias));
+	}
+	if horas > 0 || minutos > 0 || dias == 0 {
+		texto.push('T');
+		if horas > 0 {
+			texto.push_str(&format!("{}H", horas));
+		}
+		if minutos > 0 || horas == 0 {
+			texto.push_str(&format!("{}M", minutos));
+		}
+	}
+	texto
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn um_evento(linhas: &str) -> Result<Evento, String> {
+		let texto =
+			format!("BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nSUMMARY:Teste\r\n{}END:VEVENT\r\nEND:VCALENDAR\r\n", linhas);
+		ler(&texto).map(|mut eventos| eventos.remove(0))
+	}
+
+	fn recorrente(quando: Quando, regra: &str) -> Evento {
+		Evento {
+			uid: None,
+			titulo: "Teste".to_string(),
+			quando,
+			prioridade: 0,
+			status: None,
+			descricao: None,
+			local: None,
+			geo: None,
+			url: None,
+			recorrencia: Some(super::regra(regra)),
+			excecoes: Vec::new(),
+		}
+	}
+
+	fn as_nove(data: Data) -> Quando {
+		Quando::Horario { data, minuto: 9 * 60, duracao: 30 }
+	}
+
+	#[test]
+	fn keeps_the_wall_clock_time() {
+		let evento = um_evento(
+			"DTSTART;TZID=America/Sao_Paulo:20240310T093000\r\nDTEND;TZID=America/Sao_Paulo:20240310T110000\r\n",
+		)
+		.unwrap();
+		assert_eq!(evento.quando, Quando::Horario { data: (2024, 3, 10), minuto: 570, duracao: 90 });
+
+		let evento = um_evento("DTSTART:20240310T233000Z\r\nDTEND:20240311T003000Z\r\n").unwrap();
+		assert_eq!(evento.quando, Quando::Horario { data: (2024, 3, 10), minuto: 1410, duracao: 60 });
+
+		let evento = um_evento("DTSTART:20240310T093000\r\n").unwrap();
+		assert_eq!(evento.quando, Quando::Horario { data: (2024, 3, 10), minuto: 570, duracao: 0 });
+		assert!(um_evento("DTSTART:20240310T093000\r\nDTEND:20240310T090000\r\n").is_err());
+		assert!(um_evento("DTSTART:20240230T093000\r\n").is_err());
+	}
+
+	#[test]
+	fn parses_durations() {
+		assert_eq!(duracao("P1DT2H30M"), Ok(24 * 60 + 150));
+		assert_eq!(duracao("PT45M"), Ok(45));
+		assert_eq!(duracao("P1W"), Ok(7 * 24 * 60));
+		assert_eq!(duracao("-PT15M"), Ok(-15));
+		assert_eq!(duracao("PT30S"), Ok(0));
+		assert!(duracao("P1H").is_err());
+		assert!(duracao("PT5").is_err());
+		assert_eq!(duracao_iso(24 * 60 + 150), "P1DT2H30M");
+		assert_eq!(duracao_iso(0), "PT0M");
+		assert_eq!(duracao_iso(2 * 24 * 60), "P2D");
+	}
+
+	#[test]
+	fn reads_all_day_events() {
+		let evento = um_evento("DTSTART;VALUE=DATE:20241231\r\nDTEND;VALUE=DATE:20250102\r\n").unwrap();
+		assert_eq!(evento.quando, Quando::DiaInteiro { inicio: (2024, 12, 31), fim: (2025, 1, 1) });
+
+		let evento = um_evento("DTSTART;VALUE=DATE:20240228\r\n").unwrap();
+		assert_eq!(evento.quando, Quando::DiaInteiro { inicio: (2024, 2, 28), fim: (2024, 2, 28) });
+
+		let evento = um_evento("DTSTART;VALUE=DATE:20240228\r\nDURATION:P2D\r\n").unwrap();
+		assert_eq!(evento.quando, Quando::DiaInteiro { inicio: (2024, 2, 28), fim: (2024, 2, 29) });
+
+		assert!(um_evento("DTSTART;VALUE=DATE:20240228\r\nDTEND;VALUE=DATE:20240228\r\n").is_err());
+	}
+
+	#[test]
+	fn expands_recurrences() {
+		// 2024-03-04 is a Monday
+		let semanal = recorrente(as_nove((2024, 3, 6)), "FREQ=WEEKLY;BYDAY=MO,WE,FR;COUNT=4");
+		assert_eq!(
+			ocorrencias(&semanal, (1970, 1, 1), 10).datas,
+			vec![(2024, 3, 6), (2024, 3, 8), (2024, 3, 11), (2024, 3, 13)]
+		);
+
+		let mensal = recorrente(as_nove((2024, 1, 31)), "FREQ=MONTHLY;UNTIL=20240630");
+		assert_eq!(
+			ocorrencias(&mensal, (1970, 1, 1), 10).datas,
+			vec![(2024, 1, 31), (2024, 3, 31), (2024, 5, 31)]
+		);
+
+		let mut diaria = recorrente(as_nove((2024, 3, 1)), "FREQ=DAILY;INTERVAL=2;COUNT=4");
+		diaria.excecoes.push((2024, 3, 3));
+		assert_eq!(
+			ocorrencias(&diaria, (1970, 1, 1), 10),
+			Ocorrencias { datas: vec![(2024, 3, 1), (2024, 3, 5), (2024, 3, 7)], anteriores: 0, truncado: false }
+		);
+
+		let anual = recorrente(Quando::DiaInteiro { inicio: (2024, 2, 29), fim: (2024, 2, 29) }, "FREQ=YEARLY");
+		assert_eq!(
+			ocorrencias(&anual, (1970, 1, 1), 2),
+			Ocorrencias { datas: vec![(2024, 2, 29), (2028, 2, 29)], anteriores: 0, truncado: true }
+		);
+	}
+
+	#[test]
+	fn leaves_out_occurrences_before_the_range() {
+		let semanal = recorrente(as_nove((1969, 12, 18)), "FREQ=WEEKLY;COUNT=4");
+		assert_eq!(
+			ocorrencias(&semanal, (1970, 1, 1), 1),
+			Ocorrencias { datas: vec![(1970, 1, 1)], anteriores: 2, truncado: true }
+		);
+
+		let unico = recorrente(as_nove((1969, 7, 20)), "FREQ=DAILY;COUNT=1");
+		assert_eq!(ocorrencias(&unico, (1970, 1, 1), 10).anteriores, 1);
+		assert!(ocorrencias(&unico, (1970, 1, 1), 10).datas.is_empty());
+	}
+
+	#[test]
+	fn keeps_unsupported_rules_as_text() {
+		let evento = um_evento("DTSTART:20240310T093000\r\nRRULE:FREQ=HOURLY;COUNT=3\r\n").unwrap();
+		assert_eq!(evento.recorrencia, Some(Err("FREQ=HOURLY;COUNT=3".to_string())));
+		assert_eq!(ocorrencias(&evento, (1970, 1, 1), 10).datas, vec![(2024, 3, 10)]);
+		assert!(super::regra("FREQ=MONTHLY;BYDAY=MO").is_err());
+	}
+
+	#[test]
+	fn counts_days_from_the_epoch() {
+		assert_eq!(dia_da_data((1970, 1, 1)), 0);
+		assert_eq!(data_do_dia(-1), (1969, 12, 31));
+		assert_eq!(dia_da_semana(dia_da_data((2024, 3, 4))), 0);
+		for dia in [-800_000, -1, 0, 59, 11_016, 19_782, 2_932_896] {
+			assert_eq!(dia_da_data(data_do_dia(dia)), dia);
+		}
+	}
+
+	#[test]
+	fn round_trips_events() {
+		let eventos = vec![
+			Evento {
+				uid: Some("1@gcc1919".to_string()),
+				titulo: "Reuni\u{e3}o; pauta, revis\u{e3}o".to_string(),
+				quando: Quando::Horario { data: (2024, 3, 10), minuto: 570, duracao: 24 * 60 + 90 },
+				prioridade: 1,
+				status: Some("CONFIRMED".to_string()),
+				descricao: Some(format!("Linha um\n{}", "texto longo ".repeat(10))),
+				local: Some("Sala 2".to_string()),
+				geo: Some("-23.5;-46.6".to_string()),
+				url: Some("https://example.com/a,b".to_string()),
+				recorrencia: Some(super::regra("FREQ=WEEKLY;INTERVAL=2;COUNT=5;BYDAY=MO,TH")),
+				excecoes: vec![(2024, 3, 14)],
+			},
+			Evento {
+				prioridade: 0,
+				status: None,
+				descricao: None,
+				local: None,
+				geo: None,
+				url: None,
+				recorrencia: None,
+				excecoes: Vec::new(),
+				..recorrente(Quando::DiaInteiro { inicio: (2024, 12, 31), fim: (2025, 1, 2) }, "FREQ=DAILY")
+			},
+		];
+		let texto = escrever(&eventos, "20240301T120000Z");
+		assert!(texto.split("\r\n").all(|linha| linha.len() <= 75));
+		assert_eq!(ler(&texto).unwrap(), eventos);
+	}
+}
@@ -0,0 +1,290 @@
+//! A small vCard 3.0/4.0 reader and writer covering the fields an agenda contact can hold.
+//!
+//! Only the properties that map onto `Contato` are interpreted (`FN`, `N`, `TEL`, `EMAIL`,
+//! `BDAY`, `CATEGORIES` and `NOTE`); everything else is skipped.
+
+use super::conteudo::{desdobrar, desescapar, dividir, dobrar, escapar, propriedade, Propriedade};
+
+/// vCard version written on export.
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+pub enum Versao {
+	/// vCard 3.0 (RFC 2426).
+	#[value(name = "3.0")]
+	V3,
+	/// vCard 4.0 (RFC 6350).
+	#[value(name = "4.0")]
+	V4,
+}
+
+/// The parts of a vCard the agenda understands.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Cartao {
+	/// Formatted name, or the given and family names from `N` when there is no `FN`.
+	pub nome: String,
+	/// Phone numbers, preferred first.
+	pub telefones: Vec<String>,
+	/// Email addresses, preferred first.
+	pub emails: Vec<String>,
+	/// Birthday as (year, month, day); the year is missing for `--MMDD` dates.
+	pub aniversario: Option<(Option<i32>, u32, u32)>,
+	/// Values of every `CATEGORIES` property.
+	pub categorias: Vec<String>,
+	/// Free-text note.
+	pub nota: Option<String>,
+}
+
+fn preferida(propriedade: &Propriedade) -> bool {
+	propriedade.parametros.iter().any(|(nome, valor)| match nome.as_str() {
+		"PREF" => true,
+		"TYPE" => valor.split(',').any(|tipo| tipo.eq_ignore_ascii_case("pref")),
+		_ => false,
+	})
+}
+
+/// Reads every card in `texto`.
+pub fn ler(texto: &str) -> Result<Vec<Cartao>, String> {
+	let mut cartoes = Vec::new();
+	let mut atual: Option<(Cartao, Option<String>)> = None;
+
+	for (indice, linha) in desdobrar(texto).iter().enumerate() {
+		let Some(propriedade) = propriedade(linha) else {
+			return Err(format!("line {}: expected `NAME:value`, found `{}`", indice + 1, linha));
+		};
+		let vcard = propriedade.valor.trim().eq_ignore_ascii_case("VCARD");
+		match propriedade.nome.as_str() {
+			"BEGIN" if vcard => {
+				if atual.is_some() {
+					return Err(format!("line {}: nested BEGIN:VCARD", indice + 1));
+				}
+				atual = Some((Cartao::default(), None));
+			},
+			"END" if vcard => {
+				let Some((mut cartao, nome_estruturado)) = atual.take() else {
+					return Err(format!("line {}: END:VCARD without BEGIN:VCARD", indice + 1));
+				};
+				if cartao.nome.is_empty() {
+					cartao.nome = nome_estruturado.unwrap_or_default();
+				}
+				if cartao.nome.is_empty() {
+					return Err(format!("card {} has neither FN nor N", cartoes.len() + 1));
+				}
+				cartoes.push(cartao);
+			},
+			nome => {
+				let Some((cartao, nome_estruturado)) = atual.as_mut() else {
+					return Err(format!("line {}: `{}` outside of a card", indice + 1, nome));
+				};
+				interpretar(&propriedade, cartao, nome_estruturado)
+					.map_err(|erro| format!("card {}: {}", cartoes.len() + 1, erro))?;
+			},
+		}
+	}
+
+	match atual {
+		Some(_) => Err(format!("card {} is missing END:VCARD", cartoes.len() + 1)),
+		None => Ok(cartoes),
+	}
+}
+
+fn interpretar(
+	propriedade: &Propriedade,
+	cartao: &mut Cartao,
+	nome_estruturado: &mut Option<String>,
+) -> Result<(), String> {
+	match propriedade.nome.as_str() {
+		"VERSION" if !["3.0", "4.0"].contains(&propriedade.valor.trim()) =>
+			return Err(format!("vCard {} is not supported, only 3.0 and 4.0", propriedade.valor.trim())),
+		"FN" => cartao.nome = desescapar(&propriedade.valor).trim().to_string(),
+		"N" => {
+			// Family; Given; Additional; Prefixes; Suffixes
+			let partes = dividir(&propriedade.valor, ';');
+			let nome = [partes.get(3), partes.get(1), partes.get(2), partes.first(), partes.get(4)]
+				.into_iter()
+				.flatten()
+				.map(|parte| parte.trim())
+				.filter(|parte| !parte.is_empty())
+				.collect::<Vec<_>>()
+				.join(" ");
+			*nome_estruturado = Some(nome);
+		},
+		"TEL" => {
+			let valor = desescapar(&propriedade.valor);
+			let numero = valor.trim().trim_start_matches("tel:").to_string();
+			adicionar(&mut cartao.telefones, numero, preferida(propriedade));
+		},
+		"EMAIL" => {
+			let email = desescapar(&propriedade.valor).trim().to_string();
+			adicionar(&mut cartao.emails, email, preferida(propriedade));
+		},
+		// A text birthday ("circa 1800") has no date to keep
+		"BDAY" if !propriedade.parametro("VALUE").is_some_and(|valor| valor.eq_ignore_ascii_case("text")) => {
+			let aniversario =
+				data(&propriedade.valor).ok_or_else(|| format!("unsupported BDAY `{}`", propriedade.valor))?;
+			cartao.aniversario = Some(aniversario);
+		},
+		"CATEGORIES" => cartao.categorias.extend(
+			dividir(&propriedade.valor, ',')
+				.into_iter()
+				.map(|categoria| categoria.trim().to_string())
+				.filter(|categoria| !categoria.is_empty()),
+		),
+		"NOTE" => cartao.nota = Some(desescapar(&propriedade.valor)),
+		_ => {},
+	}
+	Ok(())
+}
+
+/// Writes `cartoes` in the given version, with CRLF line endings and lines folded at 75 octets.
+pub fn escrever(cartoes: &[Cartao], versao: Versao) -> String {
+	let mut saida = String::new();
+	for cartao in cartoes {
+		let mut linhas = vec![
+			"BEGIN:VCARD".to_string(),
+			match versao {
+				Versao::V3 => "VERSION:3.0",
+				Versao::V4 => "VERSION:4.0",
+			}
+			.to_string(),
+			format!("FN:{}", escapar(&cartao.nome)),
+		];
+		// N is mandatory in 3.0; the last word is taken as the family name
+		let (nome, sobrenome) = match cartao.nome.trim().rsplit_once(' ') {
+			Some((nome, sobrenome)) => (nome.trim(), sobrenome),
+			None => (cartao.nome.trim(), ""),
+		};
+		linhas.push(format!("N:{};{};;;", escapar(sobrenome), escapar(nome)));
+		for (indice, telefone) in cartao.telefones.iter().enumerate() {
+			let pref = match (indice, versao) {
+				(0, Versao::V3) if cartao.telefones.len() > 1 => ";TYPE=pref",
+				(0, Versao::V4) if cartao.telefones.len() > 1 => ";PREF=1",
+				_ => "",
+			};
+			linhas.push(format!("TEL{}:{}", pref, escapar(telefone)));
+		}
+		for email in &cartao.emails {
+			linhas.push(format!("EMAIL:{}", escapar(email)));
+		}
+		match cartao.aniversario {
+			Some((Some(ano), mes, dia)) => linhas.push(format!("BDAY:{:04}-{:02}-{:02}", ano, mes, dia)),
+			Some((None, mes, dia)) if versao == Versao::V4 => linhas.push(format!("BDAY:--{:02}{:02}", mes, dia)),
+			_ => {},
+		}
+		if !cartao.categorias.is_empty() {
+			let categorias = cartao.categorias.iter().map(|c| escapar(c)).collect::<Vec<_>>().join(",");
+			linhas.push(format!("CATEGORIES:{}", categorias));
+		}
+		if let Some(nota) = cartao.nota.as_ref().filter(|nota| !nota.is_empty()) {
+			linhas.push(format!("NOTE:{}", escapar(nota)));
+		}
+		linhas.push("END:VCARD".to_string());
+
+		for linha in linhas {
+			dobrar(&linha, &mut saida);
+		}
+	}
+	saida
+}
+
+fn adicionar(lista: &mut Vec<String>, valor: String, preferido: bool) {
+	if valor.is_empty() {
+		return;
+	}
+	if preferido {
+		lista.insert(0, valor);
+	} else {
+		lista.push(valor);
+	}
+}
+
+// `YYYY-MM-DD`, `YYYYMMDD`, `--MM-DD` or `--MMDD`, optionally followed by a time
+fn data(valor: &str) -> Option<(Option<i32>, u32, u32)> {
+	let valor = valor.trim();
+	let valor = valor.split('T').next()?;
+	let (ano, resto) = match valor.strip_prefix("--") {
+		Some(resto) => (None, resto.to_string()),
+		None => {
+			let digitos: String = valor.chars().filter(|c| *c != '-').collect();
+			if digitos.len() != 8 || !digitos.chars().all(|c| c.is_ascii_digit()) {
+				return None;
+			}
+			(Some(digitos[..4].parse().ok()?), digitos[4..].to_string())
+		},
+	};
+	let resto: String = resto.chars().filter(|c| *c != '-').collect();
+	if resto.len() != 4 || !resto.chars().all(|c| c.is_ascii_digit()) {
+		return None;
+	}
+	let mes: u32 = resto[..2].parse().ok()?;
+	let dia: u32 = resto[2..].parse().ok()?;
+	((1..=12).contains(&mes) && (1..=31).contains(&dia)).then_some((ano, mes, dia))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn reads_folded_and_escaped_properties() {
+		let texto = "BEGIN:VCARD\r\n\
+			VERSION:4.0\r\n\
+			N:Souza;Ana;Maria;Dra.;\r\n\
+			TEL;TYPE=home:+55 11 1111-1111\r\n\
+			TEL;PREF=1:+55 11 2222-2222\r\n\
+			item1.EMAIL;TYPE=work:ana@example.com\r\n\
+			BDAY:--0517\r\n\
+			CATEGORIES:Friends,Work\\, old\r\n\
+			NOTE:Primeira linha\\nsegunda\\, com v\r\n \u{ed}rgula\r\n\
+			END:VCARD\r\n";
+		let cartoes = ler(texto).unwrap();
+		assert_eq!(
+			cartoes,
+			vec![Cartao {
+				nome: "Dra. Ana Maria Souza".to_string(),
+				telefones: vec!["+55 11 2222-2222".to_string(), "+55 11 1111-1111".to_string()],
+				emails: vec!["ana@example.com".to_string()],
+				aniversario: Some((None, 5, 17)),
+				categorias: vec!["Friends".to_string(), "Work, old".to_string()],
+				nota: Some("Primeira linha\nsegunda, com v\u{ed}rgula".to_string()),
+			}]
+		);
+	}
+
+	#[test]
+	fn reads_birthday_formats() {
+		assert_eq!(data("1990-05-17"), Some((Some(1990), 5, 17)));
+		assert_eq!(data("19900517T103000Z"), Some((Some(1990), 5, 17)));
+		assert_eq!(data("--05-17"), Some((None, 5, 17)));
+		assert_eq!(data("1990-13-01"), None);
+		assert_eq!(data("May 17"), None);
+	}
+
+	#[test]
+	fn rejects_malformed_cards() {
+		assert!(ler("BEGIN:VCARD\nVERSION:2.1\nFN:Ana\nEND:VCARD\n").is_err());
+		assert!(ler("BEGIN:VCARD\nVERSION:4.0\nEND:VCARD\n").is_err());
+		assert!(ler("BEGIN:VCARD\nFN:Ana\n").is_err());
+		assert!(ler("FN:Ana\n").is_err());
+	}
+
+	#[test]
+	fn round_trips_through_both_versions() {
+		let cartao = Cartao {
+			nome: "Jo\u{e3}o da Silva".to_string(),
+			telefones: vec!["+55 11 2222-2222".to_string(), "+55 11 1111-1111".to_string()],
+			emails: vec!["joao@example.com".to_string()],
+			aniversario: Some((Some(1985), 2, 28)),
+			categorias: vec!["Family".to_string()],
+			nota: Some(format!("Anota\u{e7}\u{f5}es; {}\nfim", "longa ".repeat(20))),
+		};
+
+		let v4 = escrever(std::slice::from_ref(&cartao), Versao::V4);
+		assert!(v4.split("\r\n").all(|linha| linha.len() <= 75));
+		assert_eq!(ler(&v4).unwrap(), vec![cartao.clone()]);
+		assert_eq!(ler(&escrever(std::slice::from_ref(&cartao), Versao::V3)).unwrap(), vec![cartao.clone()]);
+
+		// vCard 3.0 has no year-less dates, so the birthday is left out
+		let sem_ano = Cartao { aniversario: Some((None, 5, 17)), ..cartao };
+		assert_eq!(ler(&escrever(std::slice::from_ref(&sem_ano), Versao::V4)).unwrap(), vec![sem_ano.clone()]);
+		assert_eq!(ler(&escrever(std::slice::from_ref(&sem_ano), Versao::V3)).unwrap()[0].aniversario, None);
+	}
+}
@@ -43,4 +43,8 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Agenda import and export utilities.
+	#[command(subcommand)]
+	Agenda(crate::agenda::AgendaCmd),
 }
@@ -0,0 +1,375 @@
+//! RPC methods for the agenda pallet.
+//!
+//! Attachment contents and the substring contact search are only available on
+//! nodes started with `--enable-offchain-indexing true`, since they read data
+//! written to the offchain database during block import.
+
+use std::sync::Arc;
+
+use codec::{Decode, Encode};
+use gcc1919_agenda_pallets::{
+	busca, chave_texto_contato, runtime_api::AgendaApi as AgendaRuntimeApi, Categoria, Compromisso, Contato,
+	CursorLista, FiltroCompromissos, FiltroContatos, Local, OrdemCompromissos, OrdemContatos, Pagina, Prioridade,
+	Status, Tarefa, MAX_PAGINA,
+};
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::{error::ErrorObject, ErrorObjectOwned},
+};
+use serde::{Deserialize, Serialize};
+use solochain_template_runtime::{opaque::Block, AccountId, Runtime};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	offchain::{OffchainStorage, STORAGE_PREFIX},
+	Bytes, H256,
+};
+
+/// A contact as returned over RPC, with text fields decoded as UTF-8.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContatoRpc {
+	/// Contact id within the account.
+	pub id: u32,
+	/// Full name.
+	pub nome: String,
+	/// Phone number as entered.
+	pub telefone: String,
+	/// Email address.
+	pub email: String,
+	/// Age in years.
+	pub idade: u32,
+	/// Birthday as a Unix timestamp at midnight UTC.
+	pub data_aniversario: u64,
+	/// Relationship category.
+	pub categoria: Categoria,
+	/// Content hashes of the attachments.
+	pub anexos: Vec<H256>,
+	/// Free-text notes.
+	pub notas: String,
+}
+
+impl From<Contato<Runtime>> for ContatoRpc {
+	fn from(contato: Contato<Runtime>) -> Self {
+		let texto = |bytes: &[u8]| String::from_utf8_lossy(bytes).into_owned();
+		Self {
+			id: contato.id,
+			nome: texto(&contato.nome),
+			telefone: texto(&contato.telefone),
+			email: texto(&contato.email),
+			idade: contato.idade,
+			data_aniversario: contato.data_aniversario,
+			categoria: contato.categoria,
+			anexos: contato.anexos.iter().map(|anexo| H256(anexo.hash)).collect(),
+			notas: texto(&contato.notas),
+		}
+	}
+}
+
+/// Where an appointment takes place.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LocalRpc {
+	/// Street address.
+	Endereco(String),
+	/// Degrees in fixed point with six decimal places.
+	Coordenadas {
+		/// Latitude times 10^6.
+		latitude: i32,
+		/// Longitude times 10^6.
+		longitude: i32,
+	},
+}
+
+/// An appointment as returned over RPC, with text fields decoded as UTF-8.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompromissoRpc {
+	/// Appointment id within the account.
+	pub id: u32,
+	/// Title.
+	pub titulo: String,
+	/// First day, as a Unix timestamp at midnight UTC.
+	pub data: u64,
+	/// Start time as `HH:MM`, empty for all-day events.
+	pub hora: String,
+	/// Priority.
+	pub prioridade: Prioridade,
+	/// Duration in minutes.
+	pub duracao: u32,
+	/// Lifecycle status.
+	pub status: Status,
+	/// Reason given when cancelled.
+	pub motivo_cancelamento: Option<String>,
+	/// Whether the event spans whole days.
+	pub dia_inteiro: bool,
+	/// Last day, as a Unix timestamp at midnight UTC.
+	pub data_fim: u64,
+	/// Where it takes place.
+	pub local: Option<LocalRpc>,
+	/// Online meeting link.
+	pub link: Option<String>,
+	/// Ids of the owner's contacts taking part.
+	pub contatos: Vec<u32>,
+	/// Content hashes of the attachments.
+	pub anexos: Vec<H256>,
+	/// Free-text notes.
+	pub notas: String,
+	/// Reminder lead times in minutes.
+	pub lembretes: Vec<u32>,
+}
+
+impl From<Compromisso<Runtime>> for CompromissoRpc {
+	fn from(compromisso: Compromisso<Runtime>) -> Self {
+		let texto = |bytes: &[u8]| String::from_utf8_lossy(bytes).into_owned();
+		Self {
+			id: compromisso.id,
+			titulo: texto(&compromisso.titulo),
+			data: compromisso.data,
+			hora: texto(&compromisso.hora),
+			prioridade: compromisso.prioridade,
+			duracao: compromisso.duracao,
+			status: compromisso.status,
+			motivo_cancelamento: compromisso.motivo_cancelamento.map(|motivo| texto(&motivo)),
+			dia_inteiro: compromisso.dia_inteiro,
+			data_fim: compromisso.data_fim,
+			local: compromisso.local.map(|local| match local {
+				Local::Endereco(endereco) => LocalRpc::Endereco(texto(&endereco)),
+				Local::Coordenadas { latitude, longitude } => LocalRpc::Coordenadas { latitude, longitude },
+			}),
+			link: compromisso.link.map(|link| texto(&link)),
+			contatos: compromisso.contatos.into_inner(),
+			anexos: compromisso.anexos.iter().map(|anexo| H256(anexo.hash)).collect(),
+			notas: texto(&compromisso.notas),
+			lembretes: compromisso.lembretes.into_inner(),
+		}
+	}
+}
+
+/// One page of results. Pass `proximo` back as `apos` to get the next page.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PaginaRpc<Item, Cursor = u32> {
+	/// Items in this page.
+	pub itens: Vec<Item>,
+	/// Cursor for the next page, or `None` when there are no more items.
+	pub proximo: Option<Cursor>,
+}
+
+impl From<Pagina<Contato<Runtime>>> for PaginaRpc<ContatoRpc> {
+	fn from(pagina: Pagina<Contato<Runtime>>) -> Self {
+		Self { itens: pagina.itens.into_iter().map(Into::into).collect(), proximo: pagina.proximo }
+	}
+}
+
+// Listing cursors travel as opaque SCALE-encoded bytes
+impl<Item, Rpc: From<Item>> From<Pagina<Item, CursorLista>> for PaginaRpc<Rpc, Bytes> {
+	fn from(pagina: Pagina<Item, CursorLista>) -> Self {
+		Self {
+			itens: pagina.itens.into_iter().map(Into::into).collect(),
+			proximo: pagina.proximo.map(|cursor| cursor.encode().into()),
+		}
+	}
+}
+
+/// Agenda RPC methods.
+#[rpc(server)]
+pub trait AgendaApi {
+	/// Returns the raw bytes of an attachment indexed by this node, if any.
+	#[method(name = "agenda_anexo")]
+	fn anexo(&self, hash: H256) -> RpcResult<Option<Bytes>>;
+
+	/// Type-ahead search: contacts with a name word, email or phone starting with each word of
+	/// `consulta`, ignoring case and accents. Results are ordered by id.
+	#[method(name = "agenda_buscarContatos")]
+	fn buscar_contatos(
+		&self,
+		conta: AccountId,
+		consulta: String,
+		apos: Option<u32>,
+		limite: u32,
+		at: Option<H256>,
+	) -> RpcResult<PaginaRpc<ContatoRpc>>;
+
+	/// Substring search over name, email, phone digits and notes, using the text indexed
+	/// offchain. Matching uses the text of the latest imported blocks; records are read at `at`.
+	#[method(name = "agenda_buscarContatosPorTrecho")]
+	fn buscar_contatos_por_trecho(
+		&self,
+		conta: AccountId,
+		trecho: String,
+		apos: Option<u32>,
+		limite: u32,
+		at: Option<H256>,
+	) -> RpcResult<PaginaRpc<ContatoRpc>>;
+
+	/// Lists the account's contacts matching `filtro`, sorted by `ordem` (by id when omitted).
+	/// `apos` is the `proximo` of the previous page, requested with the same order.
+	#[method(name = "agenda_listarContatos")]
+	fn listar_contatos(
+		&self,
+		conta: AccountId,
+		filtro: Option<FiltroContatos>,
+		ordem: Option<OrdemContatos>,
+		apos: Option<Bytes>,
+		limite: u32,
+		at: Option<H256>,
+	) -> RpcResult<PaginaRpc<ContatoRpc, Bytes>>;
+
+	/// Lists the account's appointments matching `filtro`, sorted by `ordem` (by id when omitted).
+	/// `apos` is the `proximo` of the previous page, requested with the same order.
+	#[method(name = "agenda_listarCompromissos")]
+	fn listar_compromissos(
+		&self,
+		conta: AccountId,
+		filtro: Option<FiltroCompromissos>,
+		ordem: Option<OrdemCompromissos>,
+		apos: Option<Bytes>,
+		limite: u32,
+		at: Option<H256>,
+	) -> RpcResult<PaginaRpc<CompromissoRpc, Bytes>>;
+}
+
+/// Implementation of [`AgendaApiServer`] backed by the runtime API and the node's offchain database.
+pub struct Agenda<C, S> {
+	client: Arc<C>,
+	offchain_storage: Option<S>,
+}
+
+impl<C, S> Agenda<C, S> {
+	/// Create a new instance. `offchain_storage` is `None` when the backend has no offchain DB.
+	pub fn new(client: Arc<C>, offchain_storage: Option<S>) -> Self {
+		Self { client, offchain_storage }
+	}
+}
+
+impl<C, S> AgendaApiServer for Agenda<C, S>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: AgendaRuntimeApi<Block, AccountId, Contato<Runtime>, Compromisso<Runtime>, Tarefa<Runtime>>,
+	S: OffchainStorage + 'static,
+{
+	fn anexo(&self, hash: H256) -> RpcResult<Option<Bytes>> {
+		let storage = self.offchain_storage.as_ref().ok_or_else(offchain_indisponivel)?;
+		let chave = gcc1919_agenda_pallets::chave_anexo(&hash.0);
+		Ok(storage.get(STORAGE_PREFIX, &chave).map(Into::into))
+	}
+
+	fn buscar_contatos(
+		&self,
+		conta: AccountId,
+		consulta: String,
+		apos: Option<u32>,
+		limite: u32,
+		at: Option<H256>,
+	) -> RpcResult<PaginaRpc<ContatoRpc>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let pagina = self
+			.client
+			.runtime_api()
+			.buscar_contatos(at, conta, consulta.into_bytes(), apos, limite)
+			.map_err(erro_runtime)?;
+		Ok(pagina.into())
+	}
+
+	fn buscar_contatos_por_trecho(
+		&self,
+		conta: AccountId,
+		trecho: String,
+		apos: Option<u32>,
+		limite: u32,
+		at: Option<H256>,
+	) -> RpcResult<PaginaRpc<ContatoRpc>> {
+		let storage = self.offchain_storage.as_ref().ok_or_else(offchain_indisponivel)?;
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let api = self.client.runtime_api();
+		let trecho = busca::normalizar(trecho.trim().as_bytes());
+		let limite = limite.clamp(1, MAX_PAGINA) as usize;
+
+		let mut encontrados = Vec::new();
+		let mut proximo = None;
+		let ids = api.ids_contatos(at, conta.clone()).map_err(erro_runtime)?;
+		for id in ids.into_iter().filter(|id| apos.is_none_or(|apos| *id > apos)) {
+			let Some(texto) = storage.get(STORAGE_PREFIX, &chave_texto_contato(&conta, id)) else {
+				continue;
+			};
+			if !String::from_utf8_lossy(&texto).contains(trecho.as_str()) {
+				continue;
+			}
+			if encontrados.len() == limite {
+				proximo = encontrados.last().copied();
+				break;
+			}
+			encontrados.push(id);
+		}
+
+		let contatos = api.contatos_por_ids(at, conta, encontrados).map_err(erro_runtime)?;
+		Ok(PaginaRpc { itens: contatos.into_iter().map(Into::into).collect(), proximo })
+	}
+
+	fn listar_contatos(
+		&self,
+		conta: AccountId,
+		filtro: Option<FiltroContatos>,
+		ordem: Option<OrdemContatos>,
+		apos: Option<Bytes>,
+		limite: u32,
+		at: Option<H256>,
+	) -> RpcResult<PaginaRpc<ContatoRpc, Bytes>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let pagina = self
+			.client
+			.runtime_api()
+			.listar_contatos(
+				at,
+				conta,
+				filtro.unwrap_or_default(),
+				ordem.unwrap_or_default(),
+				decodificar_cursor(apos)?,
+				limite,
+			)
+			.map_err(erro_runtime)?;
+		Ok(pagina.into())
+	}
+
+	fn listar_compromissos(
+		&self,
+		conta: AccountId,
+		filtro: Option<FiltroCompromissos>,
+		ordem: Option<OrdemCompromissos>,
+		apos: Option<Bytes>,
+		limite: u32,
+		at: Option<H256>,
+	) -> RpcResult<PaginaRpc<CompromissoRpc, Bytes>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let pagina = self
+			.client
+			.runtime_api()
+			.listar_compromissos(
+				at,
+				conta,
+				filtro.unwrap_or_default(),
+				ordem.unwrap_or_default(),
+				decodificar_cursor(apos)?,
+				limite,
+			)
+			.map_err(erro_runtime)?;
+		Ok(pagina.into())
+	}
+}
+
+fn decodificar_cursor(apos: Option<Bytes>) -> RpcResult<Option<CursorLista>> {
+	apos.map(|bytes| {
+		CursorLista::decode(&mut &bytes[..])
+			.map_err(|_| ErrorObject::owned(3, "Invalid listing cursor", None::<()>))
+	})
+	.transpose()
+}
+
+fn offchain_indisponivel() -> ErrorObjectOwned {
+	ErrorObject::owned(1, "Offchain storage is not available on this node", None::<()>)
+}
+
+fn erro_runtime(erro: sp_api::ApiError) -> ErrorObjectOwned {
+	ErrorObject::owned(2, "Runtime API call failed", Some(erro.to_string()))
+}
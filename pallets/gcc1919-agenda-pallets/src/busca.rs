@@ -0,0 +1,69 @@
+// Normalização usada pelo índice de busca de contatos: minúsculas e sem acentos, para que "joão",
+// "JOAO" e "João" caiam no mesmo termo. O nó usa as mesmas funções na busca por trecho.
+
+use scale_info::prelude::{string::String, vec::Vec};
+
+fn sem_acento(c: char) -> char {
+    match c {
+        'á' | 'à' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+        'é' | 'è' | 'ê' | 'ë' => 'e',
+        'í' | 'ì' | 'î' | 'ï' => 'i',
+        'ó' | 'ò' | 'ô' | 'õ' | 'ö' => 'o',
+        'ú' | 'ù' | 'û' | 'ü' => 'u',
+        'ç' => 'c',
+        'ñ' => 'n',
+        'ý' | 'ÿ' => 'y',
+        outro => outro,
+    }
+}
+
+pub fn normalizar(texto: &[u8]) -> String {
+    String::from_utf8_lossy(texto).chars().flat_map(char::to_lowercase).map(sem_acento).collect()
+}
+
+fn somente_digitos(texto: &[u8]) -> Vec<u8> {
+    texto.iter().copied().filter(u8::is_ascii_digit).collect()
+}
+
+// Cada palavra do nome, o e-mail inteiro e os dígitos do telefone
+pub fn termos_contato(nome: &[u8], telefone: &[u8], email: &[u8]) -> Vec<Vec<u8>> {
+    let mut termos: Vec<Vec<u8>> = normalizar(nome)
+        .split(|c: char| !c.is_alphanumeric())
+        .map(|palavra| palavra.as_bytes().to_vec())
+        .collect();
+    termos.push(normalizar(email).trim().as_bytes().to_vec());
+    termos.push(somente_digitos(telefone));
+    termos.retain(|termo| !termo.is_empty());
+    termos.sort();
+    termos.dedup();
+    termos
+}
+
+// Um telefone digitado com pontuação vira um termo só de dígitos; o resto é separado por espaços
+// e cada parte precisa ser prefixo de algum termo do contato
+pub fn termos_consulta(consulta: &[u8]) -> Vec<Vec<u8>> {
+    let consulta = normalizar(consulta);
+    let telefone = consulta.chars().any(|c| c.is_ascii_digit()) &&
+        consulta.chars().all(|c| c.is_ascii_digit() || " ()-+.".contains(c));
+    if telefone {
+        return Vec::from([somente_digitos(consulta.as_bytes())]);
+    }
+    consulta.split_whitespace().map(|parte| parte.as_bytes().to_vec()).collect()
+}
+
+pub fn corresponde(termos_consulta: &[Vec<u8>], termos_contato: &[Vec<u8>]) -> bool {
+    termos_consulta
+        .iter()
+        .all(|parte| termos_contato.iter().any(|termo| termo.starts_with(parte)))
+}
+
+// Texto gravado no banco offchain para a busca por trecho, incluindo as notas
+pub fn texto_completo(nome: &[u8], telefone: &[u8], email: &[u8], notas: &[u8]) -> Vec<u8> {
+    let mut texto = String::new();
+    for campo in [nome, email, notas] {
+        texto.push_str(&normalizar(campo));
+        texto.push('\n');
+    }
+    texto.push_str(&String::from_utf8_lossy(&somente_digitos(telefone)));
+    texto.into_bytes()
+}
@@ -0,0 +1,92 @@
+use crate::{Call, Config, Error, Pallet};
+use codec::{Decode, Encode};
+use core::marker::PhantomData;
+use frame_support::{
+    dispatch::DispatchInfo,
+    sp_runtime::{
+        traits::{DispatchInfoOf, Dispatchable, SignedExtension},
+        transaction_validity::{
+            InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+        },
+    },
+    traits::IsSubType,
+};
+use scale_info::TypeInfo;
+
+// Códigos de `InvalidTransaction::Custom` devolvidos ao pool de transações
+pub const COTA_DE_ESCRITAS_EXCEDIDA: u8 = 0;
+pub const LIMITE_ATINGIDO: u8 = 1;
+
+// Recusa chamadas da agenda acima da cota da conta já na validação, antes de ocuparem espaço no bloco.
+// Só a chamada de nível mais alto é examinada: chamadas da agenda dentro de `utility::batch`, de um
+// proxy ou de um multisig passam pelo pool sem checagem. Isso é só um filtro antecipado; quem garante
+// a cota e os limites de itens é a própria chamada, que cobra `registrar_escrita` ao ser despachada,
+// esteja ela embrulhada ou não.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct VerificarCota<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> VerificarCota<T> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: Config + Send + Sync> Default for VerificarCota<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Config + Send + Sync> core::fmt::Debug for VerificarCota<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "VerificarCota")
+    }
+}
+
+impl<T: Config + Send + Sync> SignedExtension for VerificarCota<T>
+where
+    T::RuntimeCall: Dispatchable<Info = DispatchInfo> + IsSubType<Call<T>>,
+{
+    const IDENTIFIER: &'static str = "VerificarCota";
+    type AccountId = T::AccountId;
+    type Call = T::RuntimeCall;
+    type AdditionalSigned = ();
+    type Pre = ();
+
+    fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        quem: &Self::AccountId,
+        call: &Self::Call,
+        _info: &DispatchInfoOf<Self::Call>,
+        _len: usize,
+    ) -> TransactionValidity {
+        if let Some(chamada) = call.is_sub_type() {
+            Pallet::<T>::verificar_cota(quem, chamada).map_err(|erro| match erro {
+                Error::<T>::CotaDeEscritasExcedida => InvalidTransaction::Custom(COTA_DE_ESCRITAS_EXCEDIDA),
+                _ => InvalidTransaction::Custom(LIMITE_ATINGIDO),
+            })?;
+        }
+        Ok(ValidTransaction::default())
+    }
+
+    // Repete a checagem na inclusão no bloco: uma transação que passou na validação mas chegou
+    // depois de a conta esgotar as `MaxEscritasPorJanela` escritas da janela de `JanelaEscritas`
+    // blocos (1000 a cada 600 no runtime) é recusada como inválida e sai do pool. As transações
+    // seguintes da mesma conta ficam presas esperando esse nonce até expirarem ou serem
+    // reenviadas, então clientes que enviam muitas chamadas devem respeitar a cota antes de
+    // assinar, de preferência usando os lotes.
+    fn pre_dispatch(
+        self,
+        quem: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        self.validate(quem, call, info, len).map(|_| ())
+    }
+}
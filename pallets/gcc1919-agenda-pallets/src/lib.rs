@@ -8,15 +8,36 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+pub mod busca;
+pub mod extensao;
+pub mod migrations;
+pub mod runtime_api;
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
-    use frame_support::pallet_prelude::*;
+    use frame_support::{
+        pallet_prelude::*,
+        sp_runtime::traits::{Saturating, Zero},
+        storage::with_storage_layer,
+        traits::{
+            fungible::{self, MutateHold},
+            tokens::{Fortitude, Precision, Restriction},
+            UnixTime,
+        },
+    };
     use frame_system::pallet_prelude::*;
+    use serde::{Deserialize, Serialize};
     use scale_info::prelude::vec::Vec;
     use scale_info::prelude::string::String;
 
+    pub type BalanceOf<T> =
+        <<T as Config>::Moeda as fungible::Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     #[pallet::config]
@@ -27,9 +48,81 @@ pub mod pallet {
         type MaxEmailLength: Get<u32>;
         type MaxTituloLength: Get<u32>;
         type MaxHoraLength: Get<u32>;
+        type Tempo: UnixTime;
+        type MaxCapacidade: Get<u32>;
+        type RuntimeHoldReason: From<HoldReason>;
+        type Moeda: fungible::MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>;
+        type MaxMotivoLength: Get<u32>;
+        // Limites, em minutos, da duração de compromissos com hora marcada
+        type MinDuracao: Get<u32>;
+        type MaxDuracao: Get<u32>;
+        type MaxEnderecoLength: Get<u32>;
+        type MaxLinkLength: Get<u32>;
+        type MaxContatosPorCompromisso: Get<u32>;
+        type MaxItensChecklist: Get<u32>;
+        type MaxItemLength: Get<u32>;
+        type MaxAnexos: Get<u32>;
+        type MaxMimeLength: Get<u32>;
+        type MaxLocalizadorLength: Get<u32>;
+        // Bytes de conteúdo aceitos em `anexar`; arquivos maiores vão só com hash e localizador
+        type MaxDadosAnexo: Get<u32>;
+        type MaxNotasLength: Get<u32>;
+        type MaxLembretes: Get<u32>;
+        type MaxHistoricoReagendamentos: Get<u32>;
+        // Quantos compromissos o `on_idle` examina por bloco ao aplicar a retenção
+        type MaxPodaPorBloco: Get<u32>;
+        // Limites de itens vivos por conta
+        type MaxContatosPorConta: Get<u32>;
+        type MaxCompromissosPorConta: Get<u32>;
+        // Quando verdadeiro, ids de itens apagados são reaproveitados antes de avançar o contador
+        type ReutilizarIds: Get<bool>;
+        // Cota de chamadas por conta a cada `JanelaEscritas` blocos
+        type MaxEscritasPorJanela: Get<u32>;
+        type JanelaEscritas: Get<BlockNumberFor<Self>>;
+        // Se os eventos de contatos, compromissos e tarefas levam uma cópia do registro
+        type Verbosidade: Get<Verbosidade>;
+        // Quantos bytes iniciais de cada termo de um contato entram no índice de busca
+        type MaxPrefixoBusca: Get<u32>;
+        // Itens aceitos por chamada em lote
+        type MaxLote: Get<u32>;
+        // Limitam a limpeza feita ao remover um contato: os compromissos vinculados a ele e os
+        // modelos da conta, que podem tê-lo como participante
+        type MaxCompromissosPorContato: Get<u32>;
+        type MaxModelosPorConta: Get<u32>;
+    }
+
+    // Prefixo das chaves em que o conteúdo dos anexos é gravado no banco offchain do nó
+    pub const PREFIXO_ANEXO: &[u8] = b"gcc1919-agenda::anexo::";
+
+    // Tempo de execução, em picossegundos, gasto por byte de conteúdo em `anexar`
+    pub const PESO_POR_BYTE_ANEXO: u64 = 2_000;
+
+    pub fn chave_anexo(hash: &[u8; 32]) -> Vec<u8> {
+        let mut chave = PREFIXO_ANEXO.to_vec();
+        chave.extend_from_slice(hash);
+        chave
+    }
+
+    // Texto normalizado de cada contato, para a busca por trecho feita pelo nó
+    pub const PREFIXO_TEXTO_CONTATO: &[u8] = b"gcc1919-agenda::texto-contato::";
+
+    pub fn chave_texto_contato<AccountId: Encode>(conta: &AccountId, id: u32) -> Vec<u8> {
+        let mut chave = PREFIXO_TEXTO_CONTATO.to_vec();
+        conta.encode_to(&mut chave);
+        id.encode_to(&mut chave);
+        chave
+    }
+
+    // Maior página devolvida pelas consultas paginadas
+    pub const MAX_PAGINA: u32 = 100;
+
+    #[pallet::composite_enum]
+    pub enum HoldReason {
+        // Caução do cliente enquanto a reserva de um horário está em aberto
+        DepositoReserva,
     }
 
-    #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[derive(CloneNoBound, Encode, Decode, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
     pub struct Contato<T: Config> {
         pub id: u32,
         pub nome: BoundedVec<u8, T::MaxNomeLength>,
@@ -38,9 +131,11 @@ pub mod pallet {
         pub idade: u32,
         pub data_aniversario: u64,
         pub categoria: Categoria,
+        pub anexos: BoundedVec<Anexo<T>, T::MaxAnexos>,
+        pub notas: BoundedVec<u8, T::MaxNotasLength>,
     }
 
-    #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen, Serialize, Deserialize)]
     pub enum Categoria {
         Amigo,
         Familiar,
@@ -48,7 +143,7 @@ pub mod pallet {
         Outro,
     }
 
-    #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[derive(CloneNoBound, Encode, Decode, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
     pub struct Compromisso<T: Config> {
         pub id: u32,
         pub titulo: BoundedVec<u8, T::MaxTituloLength>,
@@ -56,15 +151,256 @@ pub mod pallet {
         pub hora: BoundedVec<u8, T::MaxHoraLength>,
         pub prioridade: Prioridade,
         pub duracao: u32,
+        pub status: Status,
+        pub motivo_cancelamento: Option<BoundedVec<u8, T::MaxMotivoLength>>,
+        // Eventos de dia inteiro não têm hora e ocupam de `data` até o fim de `data_fim`
+        pub dia_inteiro: bool,
+        pub data_fim: u64,
+        pub local: Option<Local<T>>,
+        pub link: Option<BoundedVec<u8, T::MaxLinkLength>>,
+        // Ids de contatos do próprio dono do compromisso
+        pub contatos: BoundedVec<u32, T::MaxContatosPorCompromisso>,
+        pub anexos: BoundedVec<Anexo<T>, T::MaxAnexos>,
+        pub notas: BoundedVec<u8, T::MaxNotasLength>,
+        // Antecedências, em minutos, em que o dono quer ser lembrado
+        pub lembretes: BoundedVec<u32, T::MaxLembretes>,
+    }
+
+    // Valores padrão para criar compromissos recorrentes com `criar_de_modelo`
+    #[derive(CloneNoBound, Encode, Decode, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+    pub struct Modelo<T: Config> {
+        pub id: u32,
+        pub titulo: BoundedVec<u8, T::MaxTituloLength>,
+        pub duracao: u32,
+        pub prioridade: Prioridade,
+        pub lembretes: BoundedVec<u32, T::MaxLembretes>,
+        // Ids de contatos do dono do modelo
+        pub participantes: BoundedVec<u32, T::MaxContatosPorCompromisso>,
+    }
+
+    // Referência a um arquivo guardado fora da chain, identificado pelo blake2-256 do conteúdo
+    #[derive(CloneNoBound, Encode, Decode, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+    pub struct Anexo<T: Config> {
+        pub hash: [u8; 32],
+        pub tamanho: u64,
+        pub mime: BoundedVec<u8, T::MaxMimeLength>,
+        // Onde buscar o arquivo, por exemplo um CID do IPFS
+        pub localizador: Option<BoundedVec<u8, T::MaxLocalizadorLength>>,
+    }
+
+    #[derive(Clone, Copy, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum AlvoAnexo {
+        Contato(u32),
+        Compromisso(u32),
+    }
+
+    #[derive(CloneNoBound, Encode, Decode, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+    pub enum Local<T: Config> {
+        Endereco(BoundedVec<u8, T::MaxEnderecoLength>),
+        // Graus em ponto fixo com seis casas decimais (-23.550520 => -23_550_520)
+        Coordenadas { latitude: i32, longitude: i32 },
     }
 
-    #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen, Serialize, Deserialize)]
     pub enum Prioridade {
         Alta,
         Media,
         Baixa,
     }
 
+    // Agendado -> Confirmado -> Concluido, com Cancelado possível antes da conclusão
+    #[derive(
+        Clone, Copy, Encode, Decode, PartialEq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen, Serialize, Deserialize,
+    )]
+    pub enum Status {
+        #[default]
+        Agendado,
+        Confirmado,
+        Concluido,
+        Cancelado,
+    }
+
+    impl Status {
+        pub fn pode_mudar_para(&self, novo: Status) -> bool {
+            matches!(
+                (self, novo),
+                (Status::Agendado, Status::Confirmado)
+                    | (Status::Agendado | Status::Confirmado, Status::Concluido)
+                    | (Status::Agendado | Status::Confirmado, Status::Cancelado)
+            )
+        }
+    }
+
+    #[derive(CloneNoBound, Encode, Decode, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+    pub struct Tarefa<T: Config> {
+        pub id: u32,
+        pub titulo: BoundedVec<u8, T::MaxTituloLength>,
+        // Dia limite (à meia-noite); a tarefa fica atrasada quando o dia termina sem conclusão
+        pub prazo: Option<u64>,
+        pub prioridade: Prioridade,
+        pub concluida: bool,
+        pub checklist: BoundedVec<ItemChecklist<T>, T::MaxItensChecklist>,
+    }
+
+    #[derive(CloneNoBound, Encode, Decode, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+    pub struct ItemChecklist<T: Config> {
+        pub descricao: BoundedVec<u8, T::MaxItemLength>,
+        pub feito: bool,
+    }
+
+    #[derive(CloneNoBound, Encode, Decode, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+    pub struct Horario<T: Config> {
+        pub id: u32,
+        pub titulo: BoundedVec<u8, T::MaxTituloLength>,
+        // 0 = domingo, 6 = sábado
+        pub dia_semana: u8,
+        pub hora: BoundedVec<u8, T::MaxHoraLength>,
+        pub duracao: u32,
+        pub capacidade: u32,
+        // Antecedência mínima, em minutos, para o cliente cancelar a reserva
+        pub prazo_cancelamento: u32,
+        pub deposito: BalanceOf<T>,
+    }
+
+    #[derive(CloneNoBound, Encode, Decode, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+    pub struct Reserva<T: Config> {
+        pub cliente: T::AccountId,
+        pub compromisso_cliente: u32,
+        pub compromisso_profissional: u32,
+        pub inicio: u64,
+        pub limite_cancelamento: u64,
+        pub deposito: BalanceOf<T>,
+    }
+
+    // Campos de um contato nas chamadas em lote, no mesmo formato de `criar_contato`
+    #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+    pub struct NovoContato {
+        pub nome: String,
+        pub telefone: String,
+        pub email: String,
+        pub idade: u32,
+        pub data_aniversario: String,
+        pub categoria: Categoria,
+        // `None` cria o contato sem notas ou mantém as que ele já tem na atualização
+        pub notas: Option<String>,
+    }
+
+    // Campos de um compromisso nas chamadas em lote, no mesmo formato de `criar_compromisso`
+    #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+    pub struct NovoCompromisso {
+        pub titulo: String,
+        pub data: String,
+        pub hora: String,
+        pub prioridade: Prioridade,
+        pub duracao: u32,
+    }
+
+    #[derive(Clone, Copy, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+    pub enum ModoLote {
+        // A primeira falha desfaz o lote inteiro e a chamada falha com o erro do item
+        TudoOuNada,
+        // Itens com falha são pulados e os demais aplicados
+        MelhorEsforco,
+    }
+
+    #[derive(Clone, Copy, Encode, Decode, PartialEq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum Privacidade {
+        // Os intervalos ocupados aparecem nas consultas de disponibilidade
+        #[default]
+        Publica,
+        // A conta não expõe nenhuma informação de agenda
+        Privada,
+    }
+
+    // `Completa` inclui nos eventos o registro como ficou (ou como era, na remoção), para indexadores
+    // não precisarem ler o storage; `Minima` mantém só quem e o id
+    #[derive(Clone, Copy, Encode, Decode, PartialEq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum Verbosidade {
+        #[default]
+        Minima,
+        Completa,
+    }
+
+    // O que fazer com compromissos que terminaram há mais de `dias` dias
+    #[derive(Clone, Copy, Encode, Decode, PartialEq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum Retencao {
+        #[default]
+        Manter,
+        Apagar { dias: u32 },
+        Arquivar { dias: u32 },
+    }
+
+    // Resumo guardado no lugar de um compromisso arquivado
+    #[derive(CloneNoBound, Encode, Decode, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+    pub struct Arquivado<T: Config> {
+        pub titulo: BoundedVec<u8, T::MaxTituloLength>,
+        pub inicio: u64,
+        pub fim: u64,
+        pub status: Status,
+    }
+
+    // Em segundos desde a época Unix, com o fim exclusivo
+    #[derive(Clone, Copy, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct Intervalo {
+        pub inicio: u64,
+        pub fim: u64,
+    }
+
+    // `proximo` é o cursor para pedir a página seguinte, ou `None` quando não há mais itens
+    #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+    pub struct Pagina<Item, Cursor = u32> {
+        pub itens: Vec<Item>,
+        pub proximo: Option<Cursor>,
+    }
+
+    // Posição do último item entregue numa listagem ordenada: a chave de ordenação e o id para desempate
+    #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+    pub struct CursorLista {
+        pub chave: Vec<u8>,
+        pub id: u32,
+    }
+
+    #[derive(Clone, Copy, Encode, Decode, PartialEq, Default, RuntimeDebug, TypeInfo, Serialize, Deserialize)]
+    pub enum OrdemContatos {
+        #[default]
+        Id,
+        // Sem diferenciar maiúsculas nem acentos
+        Nome,
+        // Pelo dia e mês, como num calendário de aniversários
+        Aniversario,
+    }
+
+    #[derive(Clone, Copy, Encode, Decode, PartialEq, Default, RuntimeDebug, TypeInfo, Serialize, Deserialize)]
+    pub enum OrdemCompromissos {
+        #[default]
+        Id,
+        Inicio,
+        // Alta primeiro e, na mesma prioridade, pelo início
+        Prioridade,
+    }
+
+    #[derive(Clone, Encode, Decode, PartialEq, Default, RuntimeDebug, TypeInfo, Serialize, Deserialize)]
+    pub struct FiltroContatos {
+        pub categoria: Option<Categoria>,
+    }
+
+    // `de` e `ate` selecionam os compromissos que ocupam algum momento do intervalo, com `ate` exclusivo
+    #[derive(Clone, Encode, Decode, PartialEq, Default, RuntimeDebug, TypeInfo, Serialize, Deserialize)]
+    pub struct FiltroCompromissos {
+        pub prioridade: Option<Prioridade>,
+        pub status: Option<Status>,
+        pub de: Option<u64>,
+        pub ate: Option<u64>,
+    }
+
+    #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+    pub struct Disponibilidade<AccountId> {
+        pub ocupado: Vec<Intervalo>,
+        pub livre: Vec<Intervalo>,
+        pub sugestoes: Vec<Intervalo>,
+        pub privadas: Vec<AccountId>,
+    }
+
     #[pallet::storage]
     #[pallet::getter(fn contatos)]
     pub type Contatos<T: Config> =
@@ -85,15 +421,289 @@ pub mod pallet {
     pub type ContadorCompromissos<T: Config> =
         StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
 
+    #[pallet::storage]
+    #[pallet::getter(fn total_contatos)]
+    pub type TotalContatos<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn total_compromissos)]
+    pub type TotalCompromissos<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn ids_livres_contatos)]
+    pub type IdsLivresContatos<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BoundedVec<u32, T::MaxContatosPorConta>, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn ids_livres_compromissos)]
+    pub type IdsLivresCompromissos<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BoundedVec<u32, T::MaxCompromissosPorConta>, ValueQuery>;
+
+    // Bloco em que a janela atual da conta começou e quantas escritas ela já fez nessa janela
+    #[pallet::storage]
+    #[pallet::getter(fn escritas)]
+    pub type Escritas<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (BlockNumberFor<T>, u32), ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn tarefas)]
+    pub type Tarefas<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, u32, Tarefa<T>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn contador_tarefas)]
+    pub type ContadorTarefas<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    // Horários anteriores de cada compromisso, do mais antigo para o mais recente
+    #[pallet::storage]
+    #[pallet::getter(fn historico_reagendamentos)]
+    pub type HistoricoReagendamentos<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        u32,
+        BoundedVec<Intervalo, T::MaxHistoricoReagendamentos>,
+        ValueQuery,
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn modelos)]
+    pub type Modelos<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, u32, Modelo<T>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn contador_modelos)]
+    pub type ContadorModelos<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn horarios)]
+    pub type Horarios<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, u32, Horario<T>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn contador_horarios)]
+    pub type ContadorHorarios<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    // (profissional, horário, data) -> reservas feitas para aquela ocorrência do horário
+    #[pallet::storage]
+    #[pallet::getter(fn reservas)]
+    pub type Reservas<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, T::AccountId>,
+            NMapKey<Blake2_128Concat, u32>,
+            NMapKey<Blake2_128Concat, u64>,
+        ),
+        BoundedVec<Reserva<T>, T::MaxCapacidade>,
+        ValueQuery,
+    >;
+
+    // (dono, compromisso) -> (profissional, horário, data) da reserva que originou o compromisso
+    #[pallet::storage]
+    #[pallet::getter(fn reserva_do_compromisso)]
+    pub type ReservaDoCompromisso<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        u32,
+        (T::AccountId, u32, u64),
+    >;
+
+    // (dono, prefixo, contato): prefixos normalizados das palavras do nome, do e-mail e dos dígitos do telefone
+    #[pallet::storage]
+    pub type IndiceBuscaContatos<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, T::AccountId>,
+            NMapKey<Blake2_128Concat, BoundedVec<u8, T::MaxPrefixoBusca>>,
+            NMapKey<Blake2_128Concat, u32>,
+        ),
+        (),
+        OptionQuery,
+    >;
+
+    // (dono, contato, compromisso): índice reverso dos contatos vinculados a compromissos
+    #[pallet::storage]
+    pub type CompromissosDoContato<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, T::AccountId>,
+            NMapKey<Blake2_128Concat, u32>,
+            NMapKey<Blake2_128Concat, u32>,
+        ),
+        (),
+        OptionQuery,
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn privacidade)]
+    pub type Privacidades<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, Privacidade, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn retencao)]
+    pub type Retencoes<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, Retencao, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn arquivados)]
+    pub type Arquivados<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, u32, Arquivado<T>>;
+
+    // Chave bruta do último compromisso examinado, para a poda continuar no bloco seguinte
+    #[pallet::storage]
+    pub type CursorPoda<T: Config> = StorageValue<_, BoundedVec<u8, ConstU32<256>>>;
+
+    // Contato pré-carregado no genesis, com a data no mesmo formato dd/mm/aaaa das chamadas
+    #[derive(Clone, PartialEq, RuntimeDebug, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase", deny_unknown_fields)]
+    pub struct ContatoGenesis {
+        pub nome: String,
+        pub telefone: String,
+        pub email: String,
+        pub idade: u32,
+        pub data_aniversario: String,
+        pub categoria: Categoria,
+    }
+
+    #[derive(Clone, PartialEq, RuntimeDebug, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase", deny_unknown_fields)]
+    pub struct CompromissoGenesis {
+        pub titulo: String,
+        pub data: String,
+        pub hora: String,
+        pub prioridade: Prioridade,
+        pub duracao: u32,
+    }
+
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        pub contatos: Vec<(T::AccountId, ContatoGenesis)>,
+        pub compromissos: Vec<(T::AccountId, CompromissoGenesis)>,
+    }
+
+    // Os itens passam pelas mesmas validações das chamadas; qualquer dado inválido aborta o genesis
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            for (quem, contato) in self.contatos.iter().cloned() {
+                Pallet::<T>::novo_contato(
+                    &quem,
+                    contato.nome,
+                    contato.telefone,
+                    contato.email,
+                    contato.idade,
+                    contato.data_aniversario,
+                    contato.categoria,
+                )
+                .expect("contato inválido no genesis");
+            }
+            for (quem, compromisso) in self.compromissos.iter().cloned() {
+                Pallet::<T>::novo_compromisso(
+                    &quem,
+                    compromisso.titulo,
+                    compromisso.data,
+                    compromisso.hora,
+                    compromisso.prioridade,
+                    compromisso.duracao,
+                )
+                .expect("compromisso inválido no genesis");
+            }
+        }
+    }
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
-        ContatoCriado { quem: T::AccountId, id: u32 },
-        ContatoAtualizado { quem: T::AccountId, id: u32 },
-        ContatoDeletado { quem: T::AccountId, id: u32 },
-        CompromissoCriado { quem: T::AccountId, id: u32 },
-        CompromissoAtualizado { quem: T::AccountId, id: u32 },
-        CompromissoDeletado { quem: T::AccountId, id: u32 },
+        ContatoCriado { quem: T::AccountId, id: u32, contato: Option<Contato<T>> },
+        ContatoAtualizado { quem: T::AccountId, id: u32, contato: Option<Contato<T>> },
+        ContatoDeletado { quem: T::AccountId, id: u32, contato: Option<Contato<T>> },
+        CompromissoCriado { quem: T::AccountId, id: u32, compromisso: Option<Compromisso<T>> },
+        CompromissoAtualizado { quem: T::AccountId, id: u32, compromisso: Option<Compromisso<T>> },
+        CompromissoDeletado { quem: T::AccountId, id: u32, compromisso: Option<Compromisso<T>> },
+        HorarioPublicado { profissional: T::AccountId, id: u32 },
+        HorarioRemovido { profissional: T::AccountId, id: u32 },
+        HorarioReservado {
+            cliente: T::AccountId,
+            profissional: T::AccountId,
+            horario: u32,
+            data: u64,
+            compromisso_cliente: u32,
+            compromisso_profissional: u32,
+        },
+        ReservaCancelada {
+            cliente: T::AccountId,
+            profissional: T::AccountId,
+            horario: u32,
+            data: u64,
+            por: T::AccountId,
+            deposito_devolvido: BalanceOf<T>,
+        },
+        PresencaConfirmada {
+            cliente: T::AccountId,
+            profissional: T::AccountId,
+            horario: u32,
+            data: u64,
+            deposito_devolvido: BalanceOf<T>,
+        },
+        FaltaRegistrada {
+            cliente: T::AccountId,
+            profissional: T::AccountId,
+            horario: u32,
+            data: u64,
+            deposito_transferido: BalanceOf<T>,
+        },
+        PrivacidadeDefinida { quem: T::AccountId, privacidade: Privacidade },
+        CompromissoConfirmado { quem: T::AccountId, id: u32 },
+        CompromissoConcluido { quem: T::AccountId, id: u32 },
+        CompromissoCancelado {
+            quem: T::AccountId,
+            id: u32,
+            motivo: Option<BoundedVec<u8, T::MaxMotivoLength>>,
+        },
+        LocalDefinido {
+            quem: T::AccountId,
+            id: u32,
+            local: Option<Local<T>>,
+            link: Option<BoundedVec<u8, T::MaxLinkLength>>,
+        },
+        ContatosVinculados {
+            quem: T::AccountId,
+            id: u32,
+            contatos: BoundedVec<u32, T::MaxContatosPorCompromisso>,
+        },
+        TarefaCriada { quem: T::AccountId, id: u32, tarefa: Option<Tarefa<T>> },
+        TarefaAtualizada { quem: T::AccountId, id: u32, tarefa: Option<Tarefa<T>> },
+        TarefaDeletada { quem: T::AccountId, id: u32, tarefa: Option<Tarefa<T>> },
+        AnexoAdicionado { quem: T::AccountId, alvo: AlvoAnexo, hash: [u8; 32], indexado: bool },
+        AnexoRemovido { quem: T::AccountId, alvo: AlvoAnexo, hash: [u8; 32] },
+        ModeloCriado { quem: T::AccountId, id: u32 },
+        ModeloAtualizado { quem: T::AccountId, id: u32 },
+        ModeloDeletado { quem: T::AccountId, id: u32 },
+        CompromissoCriadoDeModelo { quem: T::AccountId, id: u32, modelo: u32, compromisso: Option<Compromisso<T>> },
+        // Os participantes vão no evento para que serviços offchain possam avisá-los, junto com os
+        // instantes dos lembretes que ainda vão disparar no novo horário
+        CompromissoReagendado {
+            quem: T::AccountId,
+            id: u32,
+            de: Intervalo,
+            para: Intervalo,
+            participantes: BoundedVec<u32, T::MaxContatosPorCompromisso>,
+            lembretes: BoundedVec<u64, T::MaxLembretes>,
+        },
+        RetencaoDefinida { quem: T::AccountId, retencao: Retencao },
+        HistoricoPodado { apagados: u32, arquivados: u32 },
+        // Cada item aplicado de um lote emite também o evento da chamada individual
+        ItemDoLoteFalhou { quem: T::AccountId, indice: u32, erro: DispatchError },
+        LoteConcluido { quem: T::AccountId, sucessos: u32, falhas: u32 },
     }
 
     #[pallet::error]
@@ -107,8 +717,269 @@ pub mod pallet {
         DuracaoInvalida,
         CompromissoNaoEncontrado,
         TituloMuitoLongo,
+        HorarioNaoEncontrado,
+        DiaSemanaInvalido,
+        CapacidadeInvalida,
+        DiaSemanaIncompativel,
+        HorarioNoPassado,
+        HorarioLotado,
+        ReservaDuplicada,
+        ReservaPropria,
+        ConflitoDeHorario,
+        ReservaNaoEncontrada,
+        PrazoCancelamentoExpirado,
+        SemPermissao,
+        CompromissoReservado,
+        SaldoInsuficiente,
+        ReservaNaoIniciada,
+        TransicaoInvalida,
+        MotivoMuitoLongo,
+        DataFimInvalida,
+        CoordenadasInvalidas,
+        LinkInvalido,
+        LinkMuitoLongo,
+        MuitosContatos,
+        TarefaNaoEncontrada,
+        MuitosItens,
+        ItemMuitoLongo,
+        ItemNaoEncontrado,
+        MuitosAnexos,
+        AnexoDuplicado,
+        AnexoNaoEncontrado,
+        MimeInvalido,
+        LocalizadorMuitoLongo,
+        ConteudoDivergente,
+        NotasMuitoLongas,
+        ModeloNaoEncontrado,
+        MuitosLembretes,
+        LembreteInvalido,
+        CompromissoEncerrado,
+        LimiteAtingido,
+        CotaDeEscritasExcedida,
+        ProfissionalOcupado,
     }
     
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_idle(_n: BlockNumberFor<T>, limite: Weight) -> Weight {
+            Self::podar_historico(limite)
+        }
+
+        // Um lote cheio precisa caber em uma extrínseca normal, senão a chamada nunca entraria em um bloco
+        fn integrity_test() {
+            use frame_support::{dispatch::GetDispatchInfo, traits::GetCallName};
+
+            let pesos = T::BlockWeights::get();
+            let maximo = pesos.get(DispatchClass::Normal).max_extrinsic.unwrap_or(pesos.max_block);
+            let lote = T::MaxLote::get();
+            let contato = NovoContato {
+                nome: String::new(),
+                telefone: String::new(),
+                email: String::new(),
+                idade: 0,
+                data_aniversario: String::new(),
+                categoria: Categoria::Outro,
+                notas: None,
+            };
+            let compromisso = NovoCompromisso {
+                titulo: String::new(),
+                data: String::new(),
+                hora: String::new(),
+                prioridade: Prioridade::Media,
+                duracao: 0,
+            };
+            let ids = || BoundedVec::truncate_from((0..lote).collect::<Vec<_>>());
+            let modo = ModoLote::TudoOuNada;
+            let chamadas: [Call<T>; 6] = [
+                Call::criar_contatos { itens: BoundedVec::truncate_from(vec![contato.clone(); lote as usize]), modo },
+                Call::atualizar_contatos {
+                    itens: BoundedVec::truncate_from((0..lote).map(|id| (id, contato.clone())).collect::<Vec<_>>()),
+                    modo,
+                },
+                Call::deletar_contatos { ids: ids(), modo },
+                Call::criar_compromissos { itens: BoundedVec::truncate_from(vec![compromisso.clone(); lote as usize]), modo },
+                Call::atualizar_compromissos {
+                    itens: BoundedVec::truncate_from((0..lote).map(|id| (id, compromisso.clone())).collect::<Vec<_>>()),
+                    modo,
+                },
+                Call::deletar_compromissos { ids: ids(), modo },
+            ];
+            for chamada in chamadas {
+                assert!(
+                    chamada.get_dispatch_info().weight.all_lte(maximo),
+                    "um lote cheio de {} não cabe em uma extrínseca",
+                    chamada.get_call_name(),
+                );
+            }
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn try_state(_n: BlockNumberFor<T>) -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+            Self::do_try_state()
+        }
+    }
+
+    #[cfg(any(feature = "try-runtime", test))]
+    impl<T: Config> Pallet<T> {
+        // Invariantes do storage da agenda; percorre todos os mapas, então só serve para try-runtime e testes
+        pub fn do_try_state() -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+            use scale_info::prelude::collections::BTreeMap;
+
+            // A mesma conversão das chamadas tem que reproduzir a data guardada
+            let data_valida = |data: u64| {
+                let (ano, mes, dia) = Self::data_civil(data);
+                Self::timestamp_da_data(ano, mes, dia).ok() == Some(data)
+            };
+
+            let mut contatos_por_conta: BTreeMap<T::AccountId, u32> = BTreeMap::new();
+            for (quem, id, contato) in Contatos::<T>::iter() {
+                ensure!(contato.id == id, "id do contato difere da chave");
+                ensure!(id < ContadorContatos::<T>::get(&quem), "id de contato fora do contador");
+                ensure!(data_valida(contato.data_aniversario), "data de aniversário inválida");
+                for prefixo in Self::prefixos_contato(&contato) {
+                    ensure!(
+                        IndiceBuscaContatos::<T>::contains_key((&quem, prefixo, id)),
+                        "contato sem entrada no índice de busca"
+                    );
+                }
+                *contatos_por_conta.entry(quem).or_default() += 1;
+            }
+            for (quem, total) in TotalContatos::<T>::iter() {
+                ensure!(
+                    contatos_por_conta.remove(&quem).unwrap_or(0) == total,
+                    "total de contatos divergente"
+                );
+                ensure!(total <= T::MaxContatosPorConta::get(), "contatos acima do limite da conta");
+            }
+            ensure!(contatos_por_conta.is_empty(), "conta com contatos sem total");
+            for ((quem, prefixo, id), _) in IndiceBuscaContatos::<T>::iter() {
+                let contato = Contatos::<T>::get(&quem, id).ok_or("índice de busca aponta para contato inexistente")?;
+                ensure!(
+                    Self::prefixos_contato(&contato).contains(&prefixo),
+                    "prefixo do índice de busca não pertence ao contato"
+                );
+            }
+            for (quem, livres) in IdsLivresContatos::<T>::iter() {
+                let contador = ContadorContatos::<T>::get(&quem);
+                for (posicao, id) in livres.iter().enumerate() {
+                    ensure!(*id < contador, "id livre de contato fora do contador");
+                    ensure!(!livres[..posicao].contains(id), "id livre de contato repetido");
+                    ensure!(!Contatos::<T>::contains_key(&quem, id), "id livre em uso por um contato");
+                }
+                ensure!(
+                    TotalContatos::<T>::get(&quem) as u64 + livres.len() as u64 <= contador as u64,
+                    "mais ids de contato em uso e livres do que o contador emitiu"
+                );
+            }
+
+            let mut compromissos_por_conta: BTreeMap<T::AccountId, u32> = BTreeMap::new();
+            for (quem, id, compromisso) in Compromissos::<T>::iter() {
+                ensure!(compromisso.id == id, "id do compromisso difere da chave");
+                ensure!(id < ContadorCompromissos::<T>::get(&quem), "id de compromisso fora do contador");
+                ensure!(
+                    data_valida(compromisso.data) && data_valida(compromisso.data_fim),
+                    "data de compromisso inválida"
+                );
+                ensure!(compromisso.data_fim >= compromisso.data, "compromisso termina antes de começar");
+                ensure!(
+                    compromisso.dia_inteiro
+                        || compromisso.data_fim == Self::dia_final(compromisso.data, &compromisso.hora, compromisso.duracao),
+                    "data final do compromisso divergente"
+                );
+                if compromisso.dia_inteiro {
+                    let dias = (compromisso.data_fim - compromisso.data) / 86400 + 1;
+                    ensure!(compromisso.duracao as u64 == dias * 24 * 60, "duração de dia inteiro divergente");
+                } else {
+                    ensure!(Self::hora_em_minutos(&compromisso.hora).is_ok(), "hora de compromisso inválida");
+                    ensure!(
+                        compromisso.duracao >= T::MinDuracao::get() && compromisso.duracao <= T::MaxDuracao::get(),
+                        "duração fora dos limites"
+                    );
+                }
+                for contato in compromisso.contatos.iter() {
+                    ensure!(Contatos::<T>::contains_key(&quem, contato), "compromisso vinculado a contato inexistente");
+                    ensure!(
+                        CompromissosDoContato::<T>::contains_key((&quem, contato, id)),
+                        "vínculo sem entrada no índice de contatos"
+                    );
+                }
+                *compromissos_por_conta.entry(quem).or_default() += 1;
+            }
+            for (quem, total) in TotalCompromissos::<T>::iter() {
+                ensure!(
+                    compromissos_por_conta.remove(&quem).unwrap_or(0) == total,
+                    "total de compromissos divergente"
+                );
+                ensure!(total <= T::MaxCompromissosPorConta::get(), "compromissos acima do limite da conta");
+            }
+            ensure!(compromissos_por_conta.is_empty(), "conta com compromissos sem total");
+            for (quem, livres) in IdsLivresCompromissos::<T>::iter() {
+                let contador = ContadorCompromissos::<T>::get(&quem);
+                for (posicao, id) in livres.iter().enumerate() {
+                    ensure!(*id < contador, "id livre de compromisso fora do contador");
+                    ensure!(!livres[..posicao].contains(id), "id livre de compromisso repetido");
+                    ensure!(!Compromissos::<T>::contains_key(&quem, id), "id livre em uso por um compromisso");
+                    ensure!(!Arquivados::<T>::contains_key(&quem, id), "id livre em uso por um arquivado");
+                }
+                ensure!(
+                    TotalCompromissos::<T>::get(&quem) as u64 + livres.len() as u64 <= contador as u64,
+                    "mais ids de compromisso em uso e livres do que o contador emitiu"
+                );
+            }
+
+            for ((quem, contato, id), _) in CompromissosDoContato::<T>::iter() {
+                let compromisso = Compromissos::<T>::get(&quem, id).ok_or("índice de contatos aponta para compromisso inexistente")?;
+                ensure!(compromisso.contatos.contains(&contato), "índice de contatos sem vínculo no compromisso");
+            }
+            for (quem, id, (profissional, horario, data)) in ReservaDoCompromisso::<T>::iter() {
+                ensure!(Compromissos::<T>::contains_key(&quem, id), "reserva aponta para compromisso inexistente");
+                ensure!(
+                    Reservas::<T>::get((&profissional, horario, data)).iter().any(|reserva| {
+                        (reserva.cliente == quem && reserva.compromisso_cliente == id)
+                            || (profissional == quem && reserva.compromisso_profissional == id)
+                    }),
+                    "compromisso reservado sem reserva"
+                );
+            }
+            for ((profissional, horario, data), reservas) in Reservas::<T>::iter() {
+                for reserva in reservas {
+                    ensure!(
+                        ReservaDoCompromisso::<T>::get(&reserva.cliente, reserva.compromisso_cliente)
+                            == Some((profissional.clone(), horario, data)),
+                        "reserva sem índice no compromisso do cliente"
+                    );
+                    ensure!(
+                        ReservaDoCompromisso::<T>::get(&profissional, reserva.compromisso_profissional)
+                            == Some((profissional.clone(), horario, data)),
+                        "reserva sem índice no compromisso do profissional"
+                    );
+                }
+            }
+            for (quem, id, _) in HistoricoReagendamentos::<T>::iter() {
+                ensure!(Compromissos::<T>::contains_key(&quem, id), "histórico de compromisso inexistente");
+            }
+            for (quem, id, _) in Arquivados::<T>::iter() {
+                ensure!(id < ContadorCompromissos::<T>::get(&quem), "id de arquivado fora do contador");
+                ensure!(!Compromissos::<T>::contains_key(&quem, id), "compromisso arquivado ainda ativo");
+            }
+
+            for (quem, id, tarefa) in Tarefas::<T>::iter() {
+                ensure!(tarefa.id == id, "id da tarefa difere da chave");
+                ensure!(id < ContadorTarefas::<T>::get(&quem), "id de tarefa fora do contador");
+                ensure!(tarefa.prazo.is_none_or(data_valida), "prazo de tarefa inválido");
+            }
+            for (quem, id, modelo) in Modelos::<T>::iter() {
+                ensure!(modelo.id == id, "id do modelo difere da chave");
+                ensure!(id < ContadorModelos::<T>::get(&quem), "id de modelo fora do contador");
+            }
+            for (profissional, id, horario) in Horarios::<T>::iter() {
+                ensure!(horario.id == id, "id do horário difere da chave");
+                ensure!(id < ContadorHorarios::<T>::get(&profissional), "id de horário fora do contador");
+            }
+            Ok(())
+        }
+    }
+
     impl<T: Config> Pallet<T> {
         pub fn convert_to_timestamp(date_str: Vec<u8>) -> Result<u64, Error<T>> {
             let date_str = core::str::from_utf8(&date_str).map_err(|_| Error::<T>::DataInvalida)?;
@@ -119,11 +990,21 @@ pub mod pallet {
             let day: u32 = parts[0].parse().map_err(|_| Error::<T>::DataInvalida)?;
             let month: u32 = parts[1].parse().map_err(|_| Error::<T>::DataInvalida)?;
             let year: i32 = parts[2].parse().map_err(|_| Error::<T>::DataInvalida)?;
-            if month < 1 || month > 12 || day < 1 || day > 31 {
+            Self::timestamp_da_data(year, month, day)
+        }
+
+        // Meia-noite do dia, em segundos desde a época Unix. Dias que não existem no mês e anos
+        // anteriores a 1970, que não cabem no timestamp sem sinal, são recusados.
+        pub fn timestamp_da_data(year: i32, month: u32, day: u32) -> Result<u64, Error<T>> {
+            if year < 1970 || !(1..=12).contains(&month) || day < 1 || day > Self::dias_no_mes(year, month) {
                 return Err(Error::<T>::DataInvalida);
             }
-            let timestamp = Self::date_to_unix_timestamp(year, month, day)?;
-            Ok(timestamp)
+            Self::date_to_unix_timestamp(year, month, day)
+        }
+
+        fn dias_no_mes(year: i32, month: u32) -> u32 {
+            let days_in_month = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+            days_in_month[month as usize - 1] + if month == 2 && Self::is_leap_year(year) { 1 } else { 0 }
         }
 
         fn date_to_unix_timestamp(year: i32, month: u32, day: u32) -> Result<u64, Error<T>> {
@@ -131,25 +1012,54 @@ pub mod pallet {
             for y in 1970..year {
                 days += if Self::is_leap_year(y) { 366 } else { 365 };
             }
-            let days_in_month = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
-            for m in 0..(month as usize - 1) {
-                days += days_in_month[m];
-                if m == 1 && Self::is_leap_year(year) {
-                    days += 1;
-                }
+            for m in 1..month {
+                days += Self::dias_no_mes(year, m);
             }
             days += day - 1;
             let timestamp = days as u64 * 86400;
             Ok(timestamp)
         }
 
+        // Mês (1 a 12) e dia (1 a 31) de uma data em segundos desde a época Unix
+        pub fn mes_e_dia(timestamp: u64) -> (u32, u32) {
+            let (_, mes, dia) = Self::data_civil(timestamp);
+            (mes, dia)
+        }
+
+        // Ano, mês e dia de uma data em segundos desde a época Unix
+        pub fn data_civil(timestamp: u64) -> (i32, u32, u32) {
+            let mut dias = timestamp / 86400;
+            let mut ano = 1970;
+            loop {
+                let no_ano = if Self::is_leap_year(ano) { 366 } else { 365 };
+                if dias < no_ano {
+                    break;
+                }
+                dias -= no_ano;
+                ano += 1;
+            }
+            let mut mes = 1;
+            loop {
+                let no_mes = Self::dias_no_mes(ano, mes) as u64;
+                if dias < no_mes {
+                    break;
+                }
+                dias -= no_mes;
+                mes += 1;
+            }
+            (ano, mes, dias as u32 + 1)
+        }
+
         fn is_leap_year(year: i32) -> bool {
             (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
         }
 
         pub fn validate_hour_input(hour: Vec<u8>) -> Result<(), Error<T>> {
-        
-            let hour_str = core::str::from_utf8(&hour).map_err(|_| Error::<T>::HoraInvalida)?;
+            Self::hora_em_minutos(&hour).map(|_| ())
+        }
+
+        pub fn hora_em_minutos(hour: &[u8]) -> Result<u32, Error<T>> {
+            let hour_str = core::str::from_utf8(hour).map_err(|_| Error::<T>::HoraInvalida)?;
             let parts: Vec<&str> = hour_str.split(':').collect();
             if parts.len() != 2 {
                 return Err(Error::<T>::HoraInvalida);
@@ -162,188 +1072,1982 @@ pub mod pallet {
                 return Err(Error::<T>::HoraInvalida);
             }
 
-            Ok(())
+            Ok(hours * 60 + minutes)
         }
 
         pub fn validate_duration_input(duration: u64) -> Result<(), Error<T>> {
-            // A duração é em minutos, então o valor máximo é 600 minutos (10 horas) e o mínimo é 1 minuto
-            if duration < 1 || duration > 600 {
+            // A duração é em minutos e os limites vêm da configuração do runtime
+            if duration < T::MinDuracao::get() as u64 || duration > T::MaxDuracao::get() as u64 {
                 return Err(Error::<T>::DuracaoInvalida);
             }
             Ok(())
         }
-    }
 
-    #[pallet::call]
-    impl<T: Config> Pallet<T> {
-        #[pallet::weight(Weight::default())]
-        #[pallet::call_index(0)]
-        pub fn criar_contato(
-            origin: OriginFor<T>,
-            nome: String,
-            telefone: String,
-            email: String,
-            idade: u32,
-            data_aniversario: String,
-            categoria: Categoria,
-        ) -> DispatchResult {
-            let quem = ensure_signed(origin)?;
-            let id: u32 = ContadorContatos::<T>::get(&quem);
-            let data_aniversario_parsed = Self::convert_to_timestamp(data_aniversario.into_bytes())?;
-
-            let contato = Contato {
-                id,
-                nome: BoundedVec::try_from(nome.into_bytes()).map_err(|_| Error::<T>::NomeMuitoLongo)?,
-                telefone: BoundedVec::try_from(telefone.into_bytes())
-                    .map_err(|_| Error::<T>::TelefoneMuitoLongo)?,
-                email: BoundedVec::try_from(email.into_bytes()).map_err(|_| Error::<T>::EmailMuitoLongo)?,
-                idade,
-                data_aniversario: data_aniversario_parsed,
-                categoria,
-            };
+        pub fn validate_link_input(link: &[u8]) -> Result<(), Error<T>> {
+            let resto = link
+                .strip_prefix(b"https://")
+                .or_else(|| link.strip_prefix(b"http://"))
+                .ok_or(Error::<T>::LinkInvalido)?;
+            if resto.is_empty() || resto.iter().any(|c| c.is_ascii_whitespace() || c.is_ascii_control()) {
+                return Err(Error::<T>::LinkInvalido);
+            }
+            Ok(())
+        }
 
-            Contatos::<T>::insert(&quem, id, contato);
-            ContadorContatos::<T>::insert(&quem, id + 1);
-            Self::deposit_event(Event::ContatoCriado { quem, id });
+        pub fn validate_local_input(local: &Local<T>) -> Result<(), Error<T>> {
+            if let Local::Coordenadas { latitude, longitude } = local {
+                if latitude.unsigned_abs() > 90_000_000 || longitude.unsigned_abs() > 180_000_000 {
+                    return Err(Error::<T>::CoordenadasInvalidas);
+                }
+            }
             Ok(())
         }
 
-        #[pallet::weight(Weight::default())]
-        #[pallet::call_index(1)]
-        pub fn atualizar_contato(
-            origin: OriginFor<T>,
-            id: u32,
-            nome: String,
-            telefone: String,
-            email: String,
-            idade: u32,
-            data_aniversario: String,
-            categoria: Categoria,
-        ) -> DispatchResult {
-            let quem = ensure_signed(origin)?;
-            let data_aniversario_parsed = Self::convert_to_timestamp(data_aniversario.into_bytes())?;
-            ensure!(
-                Contatos::<T>::contains_key(&quem, id),
-                Error::<T>::ContatoNaoEncontrado
-            );
+        // Dia (à meia-noite) em que termina um compromisso com hora marcada
+        pub fn dia_final(data: u64, hora: &[u8], duracao: u32) -> u64 {
+            let minutos = Self::hora_em_minutos(hora).unwrap_or(0) as u64;
+            let fim = data + (minutos + duracao as u64) * 60;
+            (fim.saturating_sub(1) / 86400 * 86400).max(data)
+        }
 
-            let contato = Contato {
-                id,
-                nome: BoundedVec::try_from(nome.into_bytes()).map_err(|_| Error::<T>::NomeMuitoLongo)?,
-                telefone: BoundedVec::try_from(telefone.into_bytes()).map_err(|_| Error::<T>::TelefoneMuitoLongo)?,
+        // 01/01/1970 foi uma quinta-feira
+        pub fn dia_da_semana(timestamp: u64) -> u8 {
+            ((timestamp / 86400 + 4) % 7) as u8
+        }
+
+        pub fn agora() -> u64 {
+            T::Tempo::now().as_secs()
+        }
+
+        // Início e fim do compromisso em segundos desde a época Unix
+        pub fn intervalo_compromisso(compromisso: &Compromisso<T>) -> (u64, u64) {
+            if compromisso.dia_inteiro {
+                return (compromisso.data, compromisso.data_fim + 86400);
+            }
+            let minutos = Self::hora_em_minutos(&compromisso.hora).unwrap_or(0);
+            let inicio = compromisso.data + minutos as u64 * 60;
+            (inicio, inicio + compromisso.duracao as u64 * 60)
+        }
+
+        // Instantes dos lembretes do compromisso que ainda estão no futuro, do mais cedo ao mais tarde
+        pub fn lembretes_pendentes(compromisso: &Compromisso<T>, agora: u64) -> BoundedVec<u64, T::MaxLembretes> {
+            let (inicio, _) = Self::intervalo_compromisso(compromisso);
+            let pendentes: Vec<u64> = compromisso
+                .lembretes
+                .iter()
+                .map(|minutos| inicio.saturating_sub(*minutos as u64 * 60))
+                .filter(|instante| *instante > agora)
+                .collect();
+            BoundedVec::truncate_from(pendentes)
+        }
+
+        pub fn tem_conflito(quem: &T::AccountId, inicio: u64, fim: u64, exceto: Option<u32>) -> bool {
+            Compromissos::<T>::iter_prefix_values(quem)
+                .filter(|compromisso| compromisso.status != Status::Cancelado)
+                .filter(|compromisso| Some(compromisso.id) != exceto)
+                .any(|compromisso| {
+                    let (outro_inicio, outro_fim) = Self::intervalo_compromisso(&compromisso);
+                    outro_inicio < fim && inicio < outro_fim
+                })
+        }
+
+        // Outras reservas do mesmo horário e data dividem a vaga; qualquer outro compromisso ativo conflita
+        fn profissional_ocupado(profissional: &T::AccountId, horario: u32, data: u64, inicio: u64, fim: u64) -> bool {
+            let origem = (profissional.clone(), horario, data);
+            Compromissos::<T>::iter_prefix_values(profissional)
+                .filter(|compromisso| compromisso.status != Status::Cancelado)
+                .filter(|compromisso| ReservaDoCompromisso::<T>::get(profissional, compromisso.id) != Some(origem.clone()))
+                .any(|compromisso| {
+                    let (outro_inicio, outro_fim) = Self::intervalo_compromisso(&compromisso);
+                    outro_inicio < fim && inicio < outro_fim
+                })
+        }
+
+        // Junta os intervalos ocupados de todas as contas públicas e sugere janelas livres com a duração pedida
+        pub fn disponibilidade(
+            contas: Vec<T::AccountId>,
+            inicio: u64,
+            fim: u64,
+            duracao: u32,
+        ) -> Disponibilidade<T::AccountId> {
+            let mut ocupado: Vec<Intervalo> = Vec::new();
+            let mut privadas = Vec::new();
+            for conta in contas {
+                if Privacidades::<T>::get(&conta) == Privacidade::Privada {
+                    privadas.push(conta);
+                    continue;
+                }
+                for compromisso in Compromissos::<T>::iter_prefix_values(&conta)
+                    .filter(|compromisso| compromisso.status != Status::Cancelado)
+                {
+                    let (c_inicio, c_fim) = Self::intervalo_compromisso(&compromisso);
+                    if c_inicio < fim && inicio < c_fim {
+                        ocupado.push(Intervalo { inicio: c_inicio.max(inicio), fim: c_fim.min(fim) });
+                    }
+                }
+            }
+
+            ocupado.sort_by_key(|intervalo| intervalo.inicio);
+            let mut unidos: Vec<Intervalo> = Vec::new();
+            for intervalo in ocupado {
+                if let Some(ultimo) = unidos.last_mut() {
+                    if intervalo.inicio <= ultimo.fim {
+                        ultimo.fim = ultimo.fim.max(intervalo.fim);
+                        continue;
+                    }
+                }
+                unidos.push(intervalo);
+            }
+
+            let mut livre = Vec::new();
+            let mut cursor = inicio;
+            for intervalo in unidos.iter() {
+                if intervalo.inicio > cursor {
+                    livre.push(Intervalo { inicio: cursor, fim: intervalo.inicio });
+                }
+                cursor = cursor.max(intervalo.fim);
+            }
+            if cursor < fim {
+                livre.push(Intervalo { inicio: cursor, fim });
+            }
+
+            let segundos = duracao as u64 * 60;
+            let sugestoes = livre
+                .iter()
+                .filter(|janela| segundos > 0 && janela.fim - janela.inicio >= segundos)
+                .map(|janela| Intervalo { inicio: janela.inicio, fim: janela.inicio + segundos })
+                .collect();
+
+            Disponibilidade { ocupado: unidos, livre, sugestoes, privadas }
+        }
+
+        pub fn compromissos_por_status(conta: &T::AccountId, status: Status) -> Vec<Compromisso<T>> {
+            Compromissos::<T>::iter_prefix_values(conta)
+                .filter(|compromisso| compromisso.status == status)
+                .collect()
+        }
+
+        pub fn compromissos_com_contato(conta: &T::AccountId, contato: u32) -> Vec<Compromisso<T>> {
+            CompromissosDoContato::<T>::iter_key_prefix((conta.clone(), contato))
+                .filter_map(|id| Compromissos::<T>::get(conta, id))
+                .collect()
+        }
+
+        // Remove o compromisso e as entradas dele no índice de contatos
+        fn remover_compromisso(quem: &T::AccountId, id: u32) {
+            HistoricoReagendamentos::<T>::remove(quem, id);
+            if let Some(compromisso) = Compromissos::<T>::take(quem, id) {
+                for contato in compromisso.contatos {
+                    CompromissosDoContato::<T>::remove((quem, contato, id));
+                }
+                TotalCompromissos::<T>::mutate(quem, |total| *total = total.saturating_sub(1));
+                // O id de um compromisso arquivado continua sendo a chave do resumo
+                if T::ReutilizarIds::get() && !Arquivados::<T>::contains_key(quem, id) {
+                    IdsLivresCompromissos::<T>::mutate(quem, |livres| {
+                        let _ = livres.try_push(id);
+                    });
+                }
+            }
+        }
+
+        // Só lê o registro para o evento quando a verbosidade pede
+        fn detalhe<R>(ler: impl FnOnce() -> Option<R>) -> Option<R> {
+            match T::Verbosidade::get() {
+                Verbosidade::Completa => ler(),
+                Verbosidade::Minima => None,
+            }
+        }
+
+        pub fn escritas_na_janela(quem: &T::AccountId) -> u32 {
+            let (inicio, escritas) = Escritas::<T>::get(quem);
+            let agora = frame_system::Pallet::<T>::block_number();
+            if agora >= inicio.saturating_add(T::JanelaEscritas::get()) {
+                0
+            } else {
+                escritas
+            }
+        }
+
+        fn registrar_escrita(quem: &T::AccountId) -> Result<(), Error<T>> {
+            Self::registrar_escritas(quem, 1)
+        }
+
+        // Os lotes contam uma escrita por item
+        fn registrar_escritas(quem: &T::AccountId, quantas: u32) -> Result<(), Error<T>> {
+            let agora = frame_system::Pallet::<T>::block_number();
+            Escritas::<T>::try_mutate(quem, |(inicio, escritas)| {
+                if agora >= inicio.saturating_add(T::JanelaEscritas::get()) {
+                    *inicio = agora;
+                    *escritas = 0;
+                }
+                ensure!(
+                    escritas.saturating_add(quantas) <= T::MaxEscritasPorJanela::get(),
+                    Error::<T>::CotaDeEscritasExcedida
+                );
+                *escritas += quantas;
+                Ok(())
+            })
+        }
+
+        // Escritas que a chamada consome da cota: uma por item nos lotes, uma nas demais
+        pub fn escritas_da_chamada(chamada: &Call<T>) -> u32 {
+            let escritas = match chamada {
+                Call::criar_contatos { itens, .. } => itens.len(),
+                Call::atualizar_contatos { itens, .. } => itens.len(),
+                Call::deletar_contatos { ids, .. } | Call::deletar_compromissos { ids, .. } => ids.len(),
+                Call::criar_compromissos { itens, .. } => itens.len(),
+                Call::atualizar_compromissos { itens, .. } => itens.len(),
+                _ => 1,
+            };
+            escritas as u32
+        }
+
+        // Mesmas checagens de cota e limite feitas nas chamadas, sem alterar o storage
+        pub fn verificar_cota(quem: &T::AccountId, chamada: &Call<T>) -> Result<(), Error<T>> {
+            let escritas = Self::escritas_da_chamada(chamada);
+            ensure!(
+                Self::escritas_na_janela(quem).saturating_add(escritas) <= T::MaxEscritasPorJanela::get(),
+                Error::<T>::CotaDeEscritasExcedida
+            );
+            // Um lote `MelhorEsforco` passa se couber ao menos um item
+            let novos = |modo: &ModoLote, quantos: usize| match modo {
+                ModoLote::TudoOuNada => quantos as u32,
+                ModoLote::MelhorEsforco => 1,
+            };
+            let (contatos, compromissos) = match chamada {
+                Call::criar_contato { .. } => (1, 0),
+                Call::criar_contatos { itens, modo } => (novos(modo, itens.len()), 0),
+                Call::criar_compromisso { .. }
+                | Call::criar_compromisso_dia_inteiro { .. }
+                | Call::criar_de_modelo { .. }
+                | Call::reservar_horario { .. } => (0, 1),
+                Call::criar_compromissos { itens, modo } => (0, novos(modo, itens.len())),
+                _ => (0, 0),
+            };
+            ensure!(
+                contatos == 0 ||
+                    TotalContatos::<T>::get(quem).saturating_add(contatos) <= T::MaxContatosPorConta::get(),
+                Error::<T>::LimiteAtingido
+            );
+            ensure!(
+                compromissos == 0 ||
+                    TotalCompromissos::<T>::get(quem).saturating_add(compromissos) <=
+                        T::MaxCompromissosPorConta::get(),
+                Error::<T>::LimiteAtingido
+            );
+            Ok(())
+        }
+
+        // Aplica `aplicar` a cada item com a cota cobrada de uma vez. No modo `TudoOuNada` o erro do
+        // primeiro item que falhar é o erro da chamada; no `MelhorEsforco` os erros viram eventos e a
+        // chamada só falha quando a cota não comporta o lote.
+        fn processar_lote<Item>(
+            quem: &T::AccountId,
+            itens: Vec<Item>,
+            modo: ModoLote,
+            mut aplicar: impl FnMut(Item) -> DispatchResult,
+        ) -> DispatchResult {
+            let total = itens.len() as u32;
+            Self::registrar_escritas(quem, total)?;
+            match modo {
+                ModoLote::TudoOuNada => {
+                    // A chamada é transacional: o erro desfaz os itens anteriores e a cota cobrada
+                    for item in itens {
+                        aplicar(item)?;
+                    }
+                    Self::deposit_event(Event::LoteConcluido { quem: quem.clone(), sucessos: total, falhas: 0 });
+                },
+                ModoLote::MelhorEsforco => {
+                    let mut falhas = 0;
+                    for (indice, item) in itens.into_iter().enumerate() {
+                        if let Err(erro) = with_storage_layer(|| aplicar(item)) {
+                            falhas += 1;
+                            Self::deposit_event(Event::ItemDoLoteFalhou { quem: quem.clone(), indice: indice as u32, erro });
+                        }
+                    }
+                    Self::deposit_event(Event::LoteConcluido { quem: quem.clone(), sucessos: total - falhas, falhas });
+                },
+            }
+            Ok(())
+        }
+
+        // Peso de uma chamada individual com as leituras e escritas dadas, mais a cota
+        pub fn peso_chamada(leituras: u64, escritas: u64) -> Weight {
+            T::DbWeight::get().reads_writes(leituras.saturating_add(1), escritas.saturating_add(1))
+        }
+
+        // Peso de um lote com `itens` itens, cada um com as leituras e escritas dadas, mais a cota
+        pub fn peso_lote(itens: usize, leituras: u64, escritas: u64) -> Weight {
+            T::DbWeight::get()
+                .reads_writes(leituras, escritas)
+                .saturating_mul(itens as u64)
+                .saturating_add(T::DbWeight::get().reads_writes(1, 1))
+        }
+
+        // Peso de `anexar` com `bytes` bytes de conteúdo: o registro e a cota, mais o hash e a cópia
+        // para o banco offchain, que crescem com o conteúdo
+        pub fn peso_anexo(bytes: usize) -> Weight {
+            T::DbWeight::get()
+                .reads_writes(2, 2)
+                .saturating_add(Weight::from_parts(PESO_POR_BYTE_ANEXO, 0).saturating_mul(bytes as u64))
+        }
+
+        // Peso de remover `itens` contatos: o registro, o total, os ids livres e o índice de busca de
+        // cada um, mais a limpeza dos vínculos com compromissos e dos participantes dos modelos
+        pub fn peso_deletar_contatos(itens: usize) -> Weight {
+            let vinculos = T::MaxCompromissosPorContato::get() as u64;
+            let modelos = T::MaxModelosPorConta::get() as u64;
+            Self::peso_lote(
+                itens,
+                3 + 2 * vinculos + 2 * modelos,
+                3 + Self::max_prefixos_contato() + 2 * vinculos + modelos,
+            )
+        }
+
+        // Entradas do índice de busca de um contato: os prefixos das palavras do nome somam no máximo o
+        // tamanho do nome, mais um prefixo de e-mail e um de telefone
+        pub fn max_prefixos_contato() -> u64 {
+            T::MaxNomeLength::get() as u64 + 2 * T::MaxPrefixoBusca::get() as u64
+        }
+
+        // Corpos das chamadas individuais de contatos e compromissos, reaproveitados pelos lotes.
+        // Cada um emite o evento do item; a cota fica por conta de quem chama.
+        fn do_criar_contato(quem: &T::AccountId, novo: NovoContato) -> Result<u32, DispatchError> {
+            let NovoContato { nome, telefone, email, idade, data_aniversario, categoria, notas } = novo;
+            let notas: BoundedVec<u8, T::MaxNotasLength> = BoundedVec::try_from(notas.unwrap_or_default().into_bytes())
+                .map_err(|_| Error::<T>::NotasMuitoLongas)?;
+            let id = Self::novo_contato(quem, nome, telefone, email, idade, data_aniversario, categoria)?;
+            if !notas.is_empty() {
+                Contatos::<T>::mutate(quem, id, |contato| {
+                    if let Some(contato) = contato {
+                        contato.notas = notas;
+                        Self::indexar_texto_contato(quem, contato);
+                    }
+                });
+            }
+            let contato = Self::detalhe(|| Contatos::<T>::get(quem, id));
+            Self::deposit_event(Event::ContatoCriado { quem: quem.clone(), id, contato });
+            Ok(id)
+        }
+
+        fn do_atualizar_contato(quem: &T::AccountId, id: u32, novo: NovoContato) -> DispatchResult {
+            let NovoContato { nome, telefone, email, idade, data_aniversario, categoria, notas } = novo;
+            let data_aniversario_parsed = Self::convert_to_timestamp(data_aniversario.into_bytes())?;
+            let anterior = Contatos::<T>::get(quem, id).ok_or(Error::<T>::ContatoNaoEncontrado)?;
+            let notas = match notas {
+                Some(notas) => BoundedVec::try_from(notas.into_bytes()).map_err(|_| Error::<T>::NotasMuitoLongas)?,
+                None => anterior.notas.clone(),
+            };
+
+            let contato = Contato {
+                id,
+                nome: BoundedVec::try_from(nome.into_bytes()).map_err(|_| Error::<T>::NomeMuitoLongo)?,
+                telefone: BoundedVec::try_from(telefone.into_bytes()).map_err(|_| Error::<T>::TelefoneMuitoLongo)?,
                 email: BoundedVec::try_from(email.into_bytes()).map_err(|_| Error::<T>::EmailMuitoLongo)?,
                 idade,
                 data_aniversario: data_aniversario_parsed,
                 categoria,
+                anexos: anterior.anexos.clone(),
+                notas,
+            };
+
+            Self::desindexar_contato(quem, &anterior);
+            Self::indexar_contato(quem, &contato);
+            Contatos::<T>::insert(quem, id, contato);
+            let contato = Self::detalhe(|| Contatos::<T>::get(quem, id));
+            Self::deposit_event(Event::ContatoAtualizado { quem: quem.clone(), id, contato });
+            Ok(())
+        }
+
+        fn do_deletar_contato(quem: &T::AccountId, id: u32) -> DispatchResult {
+            let anterior = Contatos::<T>::get(quem, id).ok_or(Error::<T>::ContatoNaoEncontrado)?;
+            Self::desindexar_contato(quem, &anterior);
+            let contato = Self::detalhe(|| Some(anterior));
+            Contatos::<T>::remove(quem, id);
+            TotalContatos::<T>::mutate(quem, |total| *total = total.saturating_sub(1));
+            if T::ReutilizarIds::get() {
+                IdsLivresContatos::<T>::mutate(quem, |livres| {
+                    let _ = livres.try_push(id);
+                });
+            }
+            for compromisso in CompromissosDoContato::<T>::iter_key_prefix((quem.clone(), id)).collect::<Vec<_>>() {
+                Compromissos::<T>::mutate(quem, compromisso, |compromisso| {
+                    if let Some(compromisso) = compromisso {
+                        compromisso.contatos.retain(|contato| *contato != id);
+                    }
+                });
+            }
+            let _ = CompromissosDoContato::<T>::clear_prefix((quem.clone(), id), u32::MAX, None);
+            for modelo in Modelos::<T>::iter_key_prefix(quem).collect::<Vec<_>>() {
+                Modelos::<T>::mutate(quem, modelo, |modelo| {
+                    if let Some(modelo) = modelo {
+                        modelo.participantes.retain(|contato| *contato != id);
+                    }
+                });
+            }
+            Self::deposit_event(Event::ContatoDeletado { quem: quem.clone(), id, contato });
+            Ok(())
+        }
+
+        fn do_criar_compromisso(quem: &T::AccountId, novo: NovoCompromisso) -> Result<u32, DispatchError> {
+            let NovoCompromisso { titulo, data, hora, prioridade, duracao } = novo;
+            let id = Self::novo_compromisso(quem, titulo, data, hora, prioridade, duracao)?;
+            let compromisso = Self::detalhe(|| Compromissos::<T>::get(quem, id));
+            Self::deposit_event(Event::CompromissoCriado { quem: quem.clone(), id, compromisso });
+            Ok(id)
+        }
+
+        fn do_atualizar_compromisso(quem: &T::AccountId, id: u32, novo: NovoCompromisso) -> DispatchResult {
+            let NovoCompromisso { titulo, data, hora, prioridade, duracao } = novo;
+            let data_parsed = Self::convert_to_timestamp(data.into_bytes())?;
+            let anterior = Compromissos::<T>::get(quem, id).ok_or(Error::<T>::CompromissoNaoEncontrado)?;
+            ensure!(
+                !ReservaDoCompromisso::<T>::contains_key(quem, id),
+                Error::<T>::CompromissoReservado
+            );
+            ensure!(
+                Self::validate_hour_input(hora.clone().into_bytes()).is_ok(),
+                Error::<T>::HoraInvalida
+            );
+            ensure!(
+                Self::validate_duration_input(duracao.into()).is_ok(),
+                Error::<T>::DuracaoInvalida
+            );
+
+            let compromisso = Compromisso {
+                id,
+                titulo: BoundedVec::try_from(titulo.into_bytes()).map_err(|_| Error::<T>::TituloMuitoLongo)?,
+                data: data_parsed,
+                data_fim: Self::dia_final(data_parsed, hora.as_bytes(), duracao),
+                hora: BoundedVec::try_from(hora.into_bytes()).map_err(|_| Error::<T>::HoraInvalida)?,
+                prioridade,
+                duracao,
+                status: anterior.status,
+                motivo_cancelamento: anterior.motivo_cancelamento,
+                dia_inteiro: false,
+                local: anterior.local,
+                link: anterior.link,
+                contatos: anterior.contatos,
+                anexos: anterior.anexos,
+                notas: anterior.notas,
+                lembretes: anterior.lembretes,
+            };
+
+            Compromissos::<T>::insert(quem, id, compromisso);
+            let compromisso = Self::detalhe(|| Compromissos::<T>::get(quem, id));
+            Self::deposit_event(Event::CompromissoAtualizado { quem: quem.clone(), id, compromisso });
+            Ok(())
+        }
+
+        fn do_deletar_compromisso(quem: &T::AccountId, id: u32) -> DispatchResult {
+            ensure!(
+                Compromissos::<T>::contains_key(quem, id),
+                Error::<T>::CompromissoNaoEncontrado
+            );
+            ensure!(
+                !ReservaDoCompromisso::<T>::contains_key(quem, id),
+                Error::<T>::CompromissoReservado
+            );
+
+            let compromisso = Self::detalhe(|| Compromissos::<T>::get(quem, id));
+            Self::remover_compromisso(quem, id);
+            Self::deposit_event(Event::CompromissoDeletado { quem: quem.clone(), id, compromisso });
+            Ok(())
+        }
+
+        // Valida os campos em texto como `criar_contato` e grava o contato
+        pub fn novo_contato(
+            quem: &T::AccountId,
+            nome: String,
+            telefone: String,
+            email: String,
+            idade: u32,
+            data_aniversario: String,
+            categoria: Categoria,
+        ) -> Result<u32, Error<T>> {
+            let data_aniversario_parsed = Self::convert_to_timestamp(data_aniversario.into_bytes())?;
+            let nome = BoundedVec::try_from(nome.into_bytes()).map_err(|_| Error::<T>::NomeMuitoLongo)?;
+            let telefone = BoundedVec::try_from(telefone.into_bytes()).map_err(|_| Error::<T>::TelefoneMuitoLongo)?;
+            let email = BoundedVec::try_from(email.into_bytes()).map_err(|_| Error::<T>::EmailMuitoLongo)?;
+            let id = Self::alocar_id_contato(quem)?;
+
+            let contato = Contato {
+                id,
+                nome,
+                telefone,
+                email,
+                idade,
+                data_aniversario: data_aniversario_parsed,
+                categoria,
+                anexos: BoundedVec::default(),
+                notas: BoundedVec::default(),
+            };
+            Self::indexar_contato(quem, &contato);
+            Contatos::<T>::insert(quem, id, contato);
+            Ok(id)
+        }
+
+        // Valida os campos em texto como `criar_compromisso` e grava o compromisso
+        pub fn novo_compromisso(
+            quem: &T::AccountId,
+            titulo: String,
+            data: String,
+            hora: String,
+            prioridade: Prioridade,
+            duracao: u32,
+        ) -> Result<u32, Error<T>> {
+            let data_parsed = Self::convert_to_timestamp(data.into_bytes())?;
+            ensure!(
+                Self::validate_hour_input(hora.clone().into_bytes()).is_ok(),
+                Error::<T>::HoraInvalida
+            );
+            ensure!(
+                Self::validate_duration_input(duracao.into()).is_ok(),
+                Error::<T>::DuracaoInvalida
+            );
+            let titulo = BoundedVec::try_from(titulo.into_bytes()).map_err(|_| Error::<T>::TituloMuitoLongo)?;
+            let hora = BoundedVec::try_from(hora.into_bytes()).map_err(|_| Error::<T>::HoraInvalida)?;
+            Self::inserir_compromisso(quem, titulo, data_parsed, hora, prioridade, duracao)
+        }
+
+        fn alocar_id_contato(quem: &T::AccountId) -> Result<u32, Error<T>> {
+            let total = TotalContatos::<T>::get(quem);
+            ensure!(total < T::MaxContatosPorConta::get(), Error::<T>::LimiteAtingido);
+            let reaproveitado = match T::ReutilizarIds::get() {
+                true => IdsLivresContatos::<T>::mutate(quem, |livres| livres.pop()),
+                false => None,
+            };
+            let id = match reaproveitado {
+                Some(id) => id,
+                None => {
+                    let id = ContadorContatos::<T>::get(quem);
+                    ContadorContatos::<T>::insert(quem, id.checked_add(1).ok_or(Error::<T>::LimiteAtingido)?);
+                    id
+                },
+            };
+            TotalContatos::<T>::insert(quem, total + 1);
+            Ok(id)
+        }
+
+        fn alocar_id_compromisso(quem: &T::AccountId) -> Result<u32, Error<T>> {
+            let total = TotalCompromissos::<T>::get(quem);
+            ensure!(total < T::MaxCompromissosPorConta::get(), Error::<T>::LimiteAtingido);
+            let reaproveitado = match T::ReutilizarIds::get() {
+                true => IdsLivresCompromissos::<T>::mutate(quem, |livres| livres.pop()),
+                false => None,
+            };
+            let id = match reaproveitado {
+                Some(id) => id,
+                None => {
+                    let id = ContadorCompromissos::<T>::get(quem);
+                    ContadorCompromissos::<T>::insert(quem, id.checked_add(1).ok_or(Error::<T>::LimiteAtingido)?);
+                    id
+                },
+            };
+            TotalCompromissos::<T>::insert(quem, total + 1);
+            Ok(id)
+        }
+
+        pub fn tarefas_atrasadas(conta: &T::AccountId) -> Vec<Tarefa<T>> {
+            let agora = Self::agora();
+            Tarefas::<T>::iter_prefix_values(conta)
+                .filter(|tarefa| !tarefa.concluida)
+                .filter(|tarefa| tarefa.prazo.is_some_and(|prazo| prazo + 86400 <= agora))
+                .collect()
+        }
+
+        // Contatos cujos termos começam com cada palavra da consulta, em ordem de id a partir de `apos`
+        pub fn buscar_contatos(
+            conta: &T::AccountId,
+            consulta: Vec<u8>,
+            apos: Option<u32>,
+            limite: u32,
+        ) -> Pagina<Contato<T>> {
+            let mut pagina = Pagina { itens: Vec::new(), proximo: None };
+            let termos = busca::termos_consulta(&consulta);
+            let Some(primeiro) = termos.first() else {
+                return pagina;
+            };
+            let prefixo = BoundedVec::<u8, T::MaxPrefixoBusca>::truncate_from(primeiro.clone());
+            let mut ids: Vec<u32> = IndiceBuscaContatos::<T>::iter_key_prefix((conta.clone(), prefixo))
+                .filter(|id| apos.is_none_or(|apos| *id > apos))
+                .collect();
+            ids.sort_unstable();
+
+            let limite = limite.clamp(1, MAX_PAGINA) as usize;
+            for contato in ids.into_iter().filter_map(|id| Contatos::<T>::get(conta, id)) {
+                // O índice só guarda os primeiros bytes, então a consulta inteira é conferida aqui
+                if !busca::corresponde(&termos, &Self::termos_contato(&contato)) {
+                    continue;
+                }
+                if pagina.itens.len() == limite {
+                    pagina.proximo = pagina.itens.last().map(|anterior: &Contato<T>| anterior.id);
+                    break;
+                }
+                pagina.itens.push(contato);
+            }
+            pagina
+        }
+
+        pub fn listar_contatos(
+            conta: &T::AccountId,
+            filtro: FiltroContatos,
+            ordem: OrdemContatos,
+            apos: Option<CursorLista>,
+            limite: u32,
+        ) -> Pagina<Contato<T>, CursorLista> {
+            let itens = Contatos::<T>::iter_prefix_values(conta)
+                .filter(|contato| filtro.categoria.as_ref().is_none_or(|categoria| contato.categoria == *categoria))
+                .map(|contato| {
+                    let chave = match ordem {
+                        OrdemContatos::Id => Vec::new(),
+                        OrdemContatos::Nome => busca::normalizar(&contato.nome).into_bytes(),
+                        OrdemContatos::Aniversario => {
+                            let (mes, dia) = Self::mes_e_dia(contato.data_aniversario);
+                            [mes as u8, dia as u8].to_vec()
+                        },
+                    };
+                    (chave, contato.id, contato)
+                })
+                .collect();
+            Self::paginar(itens, apos, limite)
+        }
+
+        pub fn listar_compromissos(
+            conta: &T::AccountId,
+            filtro: FiltroCompromissos,
+            ordem: OrdemCompromissos,
+            apos: Option<CursorLista>,
+            limite: u32,
+        ) -> Pagina<Compromisso<T>, CursorLista> {
+            let itens = Compromissos::<T>::iter_prefix_values(conta)
+                .filter(|compromisso| {
+                    let (inicio, fim) = Self::intervalo_compromisso(compromisso);
+                    filtro.prioridade.as_ref().is_none_or(|prioridade| compromisso.prioridade == *prioridade) &&
+                        filtro.status.is_none_or(|status| compromisso.status == status) &&
+                        filtro.de.is_none_or(|de| fim > de) &&
+                        filtro.ate.is_none_or(|ate| inicio < ate)
+                })
+                .map(|compromisso| {
+                    let inicio = Self::intervalo_compromisso(&compromisso).0.to_be_bytes();
+                    let chave = match ordem {
+                        OrdemCompromissos::Id => Vec::new(),
+                        OrdemCompromissos::Inicio => inicio.to_vec(),
+                        OrdemCompromissos::Prioridade => {
+                            let mut chave = compromisso.prioridade.encode();
+                            chave.extend_from_slice(&inicio);
+                            chave
+                        },
+                    };
+                    (chave, compromisso.id, compromisso)
+                })
+                .collect();
+            Self::paginar(itens, apos, limite)
+        }
+
+        // Ordena por (chave, id) e entrega até `limite` itens depois do cursor
+        fn paginar<Item>(
+            mut itens: Vec<(Vec<u8>, u32, Item)>,
+            apos: Option<CursorLista>,
+            limite: u32,
+        ) -> Pagina<Item, CursorLista> {
+            itens.sort_by(|a, b| (&a.0, a.1).cmp(&(&b.0, b.1)));
+            let mut restantes = itens
+                .into_iter()
+                .filter(|(chave, id, _)| apos.as_ref().is_none_or(|apos| (chave, *id) > (&apos.chave, apos.id)));
+            let pagina: Vec<_> = restantes.by_ref().take(limite.clamp(1, MAX_PAGINA) as usize).collect();
+            let proximo = match restantes.next() {
+                Some(_) => pagina.last().map(|(chave, id, _)| CursorLista { chave: chave.clone(), id: *id }),
+                None => None,
+            };
+            Pagina { itens: pagina.into_iter().map(|(_, _, item)| item).collect(), proximo }
+        }
+
+        pub fn ids_contatos(conta: &T::AccountId) -> Vec<u32> {
+            let mut ids: Vec<u32> = Contatos::<T>::iter_key_prefix(conta).collect();
+            ids.sort_unstable();
+            ids
+        }
+
+        pub fn contatos_por_ids(conta: &T::AccountId, ids: Vec<u32>) -> Vec<Contato<T>> {
+            ids.into_iter().filter_map(|id| Contatos::<T>::get(conta, id)).collect()
+        }
+
+        fn termos_contato(contato: &Contato<T>) -> Vec<Vec<u8>> {
+            busca::termos_contato(&contato.nome, &contato.telefone, &contato.email)
+        }
+
+        fn prefixos_contato(contato: &Contato<T>) -> Vec<BoundedVec<u8, T::MaxPrefixoBusca>> {
+            let maximo = T::MaxPrefixoBusca::get() as usize;
+            let mut prefixos: Vec<_> = Self::termos_contato(contato)
+                .iter()
+                .flat_map(|termo| (1..=termo.len().min(maximo)).map(|fim| termo[..fim].to_vec()))
+                .collect();
+            prefixos.sort();
+            prefixos.dedup();
+            prefixos.into_iter().map(BoundedVec::truncate_from).collect()
+        }
+
+        pub(crate) fn indexar_contato(quem: &T::AccountId, contato: &Contato<T>) {
+            for prefixo in Self::prefixos_contato(contato) {
+                IndiceBuscaContatos::<T>::insert((quem, prefixo, contato.id), ());
+            }
+            Self::indexar_texto_contato(quem, contato);
+        }
+
+        fn desindexar_contato(quem: &T::AccountId, contato: &Contato<T>) {
+            for prefixo in Self::prefixos_contato(contato) {
+                IndiceBuscaContatos::<T>::remove((quem, prefixo, contato.id));
+            }
+            sp_io::offchain_index::clear(&chave_texto_contato(quem, contato.id));
+        }
+
+        // Só chega ao banco offchain de nós iniciados com `--enable-offchain-indexing true`
+        fn indexar_texto_contato(quem: &T::AccountId, contato: &Contato<T>) {
+            let texto = busca::texto_completo(&contato.nome, &contato.telefone, &contato.email, &contato.notas);
+            sp_io::offchain_index::set(&chave_texto_contato(quem, contato.id), &texto);
+        }
+
+        // Ordena, remove repetidos e confere que todos os ids são contatos de `quem`
+        fn validar_contatos(
+            quem: &T::AccountId,
+            contatos: Vec<u32>,
+        ) -> Result<BoundedVec<u32, T::MaxContatosPorCompromisso>, Error<T>> {
+            let mut contatos = contatos;
+            contatos.sort();
+            contatos.dedup();
+            for contato in contatos.iter() {
+                ensure!(
+                    Contatos::<T>::contains_key(quem, contato),
+                    Error::<T>::ContatoNaoEncontrado
+                );
+            }
+            BoundedVec::try_from(contatos).map_err(|_| Error::<T>::MuitosContatos)
+        }
+
+        // Cada contato fica vinculado a no máximo `MaxCompromissosPorContato` compromissos
+        fn ensure_vinculos_livres<'a>(quem: &T::AccountId, contatos: impl Iterator<Item = &'a u32>) -> Result<(), Error<T>> {
+            for contato in contatos {
+                let vinculos = CompromissosDoContato::<T>::iter_key_prefix((quem.clone(), *contato)).count() as u32;
+                ensure!(vinculos < T::MaxCompromissosPorContato::get(), Error::<T>::LimiteAtingido);
+            }
+            Ok(())
+        }
+
+        // Lembretes em minutos antes do início, do mais distante para o mais próximo
+        fn montar_lembretes(lembretes: Vec<u32>) -> Result<BoundedVec<u32, T::MaxLembretes>, Error<T>> {
+            let mut lembretes = lembretes;
+            lembretes.sort_by(|a, b| b.cmp(a));
+            lembretes.dedup();
+            ensure!(
+                lembretes.iter().all(|minutos| *minutos <= T::MaxDuracao::get()),
+                Error::<T>::LembreteInvalido
+            );
+            BoundedVec::try_from(lembretes).map_err(|_| Error::<T>::MuitosLembretes)
+        }
+
+        // Aplica a retenção de cada conta a no máximo `MaxPodaPorBloco` compromissos, sem passar de `limite`.
+        // Compromissos com reserva ficam de fora, pois ainda podem ter depósito retido.
+        pub fn podar_historico(limite: Weight) -> Weight {
+            let db = T::DbWeight::get();
+            let por_item = db.reads_writes(3, 4 + T::MaxContatosPorCompromisso::get() as u64);
+            let mut consumido = db.reads_writes(1, 1);
+            if consumido.any_gt(limite) {
+                return Weight::zero();
+            }
+
+            let agora = Self::agora();
+            let mut iter = match CursorPoda::<T>::get() {
+                Some(cursor) => Compromissos::<T>::iter_from(cursor.into_inner()),
+                None => Compromissos::<T>::iter(),
+            };
+            let mut expirados = Vec::new();
+            let mut analisados = 0;
+            let mut terminou = false;
+            while analisados < T::MaxPodaPorBloco::get() && !consumido.saturating_add(por_item).any_gt(limite) {
+                let Some((quem, id, compromisso)) = iter.next() else {
+                    terminou = true;
+                    break;
+                };
+                analisados += 1;
+                consumido = consumido.saturating_add(por_item);
+
+                let (arquivar, dias) = match Retencoes::<T>::get(&quem) {
+                    Retencao::Manter => continue,
+                    Retencao::Apagar { dias } => (false, dias),
+                    Retencao::Arquivar { dias } => (true, dias),
+                };
+                let (inicio, fim) = Self::intervalo_compromisso(&compromisso);
+                if fim.saturating_add(dias as u64 * 86400) > agora || ReservaDoCompromisso::<T>::contains_key(&quem, id) {
+                    continue;
+                }
+                let resumo = arquivar.then_some(Arquivado { titulo: compromisso.titulo, inicio, fim, status: compromisso.status });
+                expirados.push((quem, id, resumo));
+            }
+
+            if terminou {
+                CursorPoda::<T>::kill();
+            } else if let Ok(cursor) = BoundedVec::try_from(iter.last_raw_key().to_vec()) {
+                CursorPoda::<T>::put(cursor);
+            }
+
+            let (mut apagados, mut arquivados) = (0u32, 0u32);
+            for (quem, id, resumo) in expirados {
+                // O resumo é gravado antes da remoção para que o id não volte à lista de ids livres
+                match resumo {
+                    Some(resumo) => {
+                        Arquivados::<T>::insert(&quem, id, resumo);
+                        arquivados += 1;
+                    },
+                    None => apagados += 1,
+                }
+                Self::remover_compromisso(&quem, id);
+            }
+            if apagados > 0 || arquivados > 0 {
+                Self::deposit_event(Event::HistoricoPodado { apagados, arquivados });
+            }
+            consumido
+        }
+
+        fn montar_checklist(
+            itens: Vec<(String, bool)>,
+        ) -> Result<BoundedVec<ItemChecklist<T>, T::MaxItensChecklist>, Error<T>> {
+            let itens = itens
+                .into_iter()
+                .map(|(descricao, feito)| {
+                    Ok(ItemChecklist {
+                        descricao: BoundedVec::try_from(descricao.into_bytes()).map_err(|_| Error::<T>::ItemMuitoLongo)?,
+                        feito,
+                    })
+                })
+                .collect::<Result<Vec<_>, Error<T>>>()?;
+            BoundedVec::try_from(itens).map_err(|_| Error::<T>::MuitosItens)
+        }
+
+        pub fn validate_mime_input(mime: &[u8]) -> Result<(), Error<T>> {
+            let mut partes = mime.split(|c| *c == b'/');
+            let valido = match (partes.next(), partes.next(), partes.next()) {
+                (Some(tipo), Some(subtipo), None) => {
+                    !tipo.is_empty()
+                        && !subtipo.is_empty()
+                        && mime.iter().all(|c| c.is_ascii_graphic())
+                },
+                _ => false,
+            };
+            if !valido {
+                return Err(Error::<T>::MimeInvalido);
+            }
+            Ok(())
+        }
+
+        fn alterar_anexos(
+            quem: &T::AccountId,
+            alvo: AlvoAnexo,
+            alterar: impl FnOnce(&mut BoundedVec<Anexo<T>, T::MaxAnexos>) -> Result<(), Error<T>>,
+        ) -> Result<(), Error<T>> {
+            match alvo {
+                AlvoAnexo::Contato(id) => Contatos::<T>::try_mutate(quem, id, |contato| {
+                    alterar(&mut contato.as_mut().ok_or(Error::<T>::ContatoNaoEncontrado)?.anexos)
+                }),
+                AlvoAnexo::Compromisso(id) => Compromissos::<T>::try_mutate(quem, id, |compromisso| {
+                    alterar(&mut compromisso.as_mut().ok_or(Error::<T>::CompromissoNaoEncontrado)?.anexos)
+                }),
+            }
+        }
+
+        fn mudar_status(quem: &T::AccountId, id: u32, novo: Status) -> Result<(), Error<T>> {
+            Compromissos::<T>::try_mutate(quem, id, |compromisso| {
+                let compromisso = compromisso.as_mut().ok_or(Error::<T>::CompromissoNaoEncontrado)?;
+                ensure!(compromisso.status.pode_mudar_para(novo), Error::<T>::TransicaoInvalida);
+                compromisso.status = novo;
+                Ok(())
+            })
+        }
+
+        fn retirar_reserva(
+            profissional: &T::AccountId,
+            horario: u32,
+            data: u64,
+            cliente: &T::AccountId,
+        ) -> Result<Reserva<T>, Error<T>> {
+            let mut reservas = Reservas::<T>::get((profissional, horario, data));
+            let posicao = reservas
+                .iter()
+                .position(|reserva| &reserva.cliente == cliente)
+                .ok_or(Error::<T>::ReservaNaoEncontrada)?;
+
+            let reserva = reservas.remove(posicao);
+            if reservas.is_empty() {
+                Reservas::<T>::remove((profissional, horario, data));
+            } else {
+                Reservas::<T>::insert((profissional, horario, data), reservas);
+            }
+
+            ReservaDoCompromisso::<T>::remove(cliente, reserva.compromisso_cliente);
+            ReservaDoCompromisso::<T>::remove(profissional, reserva.compromisso_profissional);
+            Ok(reserva)
+        }
+
+        fn ensure_reserva_iniciada(
+            profissional: &T::AccountId,
+            horario: u32,
+            data: u64,
+            cliente: &T::AccountId,
+        ) -> DispatchResult {
+            let reserva = Reservas::<T>::get((profissional, horario, data))
+                .into_iter()
+                .find(|reserva| &reserva.cliente == cliente)
+                .ok_or(Error::<T>::ReservaNaoEncontrada)?;
+            ensure!(Self::agora() >= reserva.inicio, Error::<T>::ReservaNaoIniciada);
+            Ok(())
+        }
+
+        fn liberar_deposito(cliente: &T::AccountId, deposito: BalanceOf<T>) -> Result<BalanceOf<T>, DispatchError> {
+            if deposito.is_zero() {
+                return Ok(deposito);
+            }
+            T::Moeda::release(&HoldReason::DepositoReserva.into(), cliente, deposito, Precision::BestEffort)
+        }
+
+        fn inserir_compromisso(
+            quem: &T::AccountId,
+            titulo: BoundedVec<u8, T::MaxTituloLength>,
+            data: u64,
+            hora: BoundedVec<u8, T::MaxHoraLength>,
+            prioridade: Prioridade,
+            duracao: u32,
+        ) -> Result<u32, Error<T>> {
+            let id = Self::alocar_id_compromisso(quem)?;
+            let data_fim = Self::dia_final(data, &hora, duracao);
+            let compromisso = Compromisso {
+                id,
+                titulo,
+                data,
+                hora,
+                prioridade,
+                duracao,
+                status: Status::Agendado,
+                motivo_cancelamento: None,
+                dia_inteiro: false,
+                data_fim,
+                local: None,
+                link: None,
+                contatos: BoundedVec::default(),
+                anexos: BoundedVec::default(),
+                notas: BoundedVec::default(),
+                lembretes: BoundedVec::default(),
+            };
+            Compromissos::<T>::insert(quem, id, compromisso);
+            Ok(id)
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        // O peso conta o máximo de entradas que um contato grava no índice de busca
+        #[pallet::weight(Pallet::<T>::peso_chamada(4, 4 + Pallet::<T>::max_prefixos_contato()))]
+        #[pallet::call_index(0)]
+        pub fn criar_contato(
+            origin: OriginFor<T>,
+            nome: String,
+            telefone: String,
+            email: String,
+            idade: u32,
+            data_aniversario: String,
+            categoria: Categoria,
+        ) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            let novo = NovoContato { nome, telefone, email, idade, data_aniversario, categoria, notas: None };
+            Self::do_criar_contato(&quem, novo)?;
+            Ok(())
+        }
+
+        // As entradas antigas do índice são removidas antes de gravar as novas
+        #[pallet::weight(Pallet::<T>::peso_chamada(2, 1 + 2 * Pallet::<T>::max_prefixos_contato()))]
+        #[pallet::call_index(1)]
+        pub fn atualizar_contato(
+            origin: OriginFor<T>,
+            id: u32,
+            nome: String,
+            telefone: String,
+            email: String,
+            idade: u32,
+            data_aniversario: String,
+            categoria: Categoria,
+        ) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            let novo = NovoContato { nome, telefone, email, idade, data_aniversario, categoria, notas: None };
+            Self::do_atualizar_contato(&quem, id, novo)
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_deletar_contatos(1))]
+        #[pallet::call_index(2)]
+        pub fn deletar_contato(origin: OriginFor<T>, id: u32) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            Self::do_deletar_contato(&quem, id)
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(4, 4))]
+        #[pallet::call_index(3)]
+        pub fn criar_compromisso(
+            origin: OriginFor<T>,
+            titulo: String,
+            data: String,
+            hora: String,
+            prioridade: Prioridade,
+            duracao: u32,
+        ) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            Self::do_criar_compromisso(&quem, NovoCompromisso { titulo, data, hora, prioridade, duracao })?;
+            Ok(())
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(3, 1))]
+        #[pallet::call_index(4)]
+        pub fn atualizar_compromisso(
+            origin: OriginFor<T>,
+            id: u32,
+            titulo: String,
+            data: String,
+            hora: String,
+            prioridade: Prioridade,
+            duracao: u32,
+        ) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            Self::do_atualizar_compromisso(&quem, id, NovoCompromisso { titulo, data, hora, prioridade, duracao })
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(7, 4 + T::MaxContatosPorCompromisso::get() as u64))]
+        #[pallet::call_index(5)]
+        pub fn deletar_compromisso(origin: OriginFor<T>, id: u32) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            Self::do_deletar_compromisso(&quem, id)
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(1, 2))]
+        #[pallet::call_index(6)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn publicar_horario(
+            origin: OriginFor<T>,
+            titulo: String,
+            dia_semana: u8,
+            hora: String,
+            duracao: u32,
+            capacidade: u32,
+            prazo_cancelamento: u32,
+            deposito: BalanceOf<T>,
+        ) -> DispatchResult {
+            let profissional = ensure_signed(origin)?;
+            Self::registrar_escrita(&profissional)?;
+            ensure!(dia_semana <= 6, Error::<T>::DiaSemanaInvalido);
+            ensure!(
+                Self::validate_hour_input(hora.clone().into_bytes()).is_ok(),
+                Error::<T>::HoraInvalida
+            );
+            ensure!(
+                Self::validate_duration_input(duracao.into()).is_ok(),
+                Error::<T>::DuracaoInvalida
+            );
+            ensure!(
+                capacidade >= 1 && capacidade <= T::MaxCapacidade::get(),
+                Error::<T>::CapacidadeInvalida
+            );
+
+            let id = ContadorHorarios::<T>::get(&profissional);
+            let horario = Horario {
+                id,
+                titulo: BoundedVec::try_from(titulo.into_bytes()).map_err(|_| Error::<T>::TituloMuitoLongo)?,
+                dia_semana,
+                hora: BoundedVec::try_from(hora.into_bytes()).map_err(|_| Error::<T>::HoraInvalida)?,
+                duracao,
+                capacidade,
+                prazo_cancelamento,
+                deposito,
+            };
+
+            Horarios::<T>::insert(&profissional, id, horario);
+            ContadorHorarios::<T>::insert(&profissional, id.checked_add(1).ok_or(Error::<T>::LimiteAtingido)?);
+            Self::deposit_event(Event::HorarioPublicado { profissional, id });
+            Ok(())
+        }
+
+        // Reservas já feitas continuam válidas; apenas novas reservas deixam de ser aceitas.
+        #[pallet::weight(Pallet::<T>::peso_chamada(1, 1))]
+        #[pallet::call_index(7)]
+        pub fn remover_horario(origin: OriginFor<T>, id: u32) -> DispatchResult {
+            let profissional = ensure_signed(origin)?;
+            Self::registrar_escrita(&profissional)?;
+            ensure!(
+                Horarios::<T>::contains_key(&profissional, id),
+                Error::<T>::HorarioNaoEncontrado
+            );
+
+            Horarios::<T>::remove(&profissional, id);
+            Self::deposit_event(Event::HorarioRemovido { profissional, id });
+            Ok(())
+        }
+
+        // A checagem de conflitos percorre todos os compromissos do cliente e do profissional, e a do
+        // profissional ainda lê a reserva de cada um; o resto é o horário, o depósito e os dois compromissos
+        #[pallet::weight(Pallet::<T>::peso_chamada(
+            11 + 3 * T::MaxCompromissosPorConta::get() as u64,
+            13,
+        ))]
+        #[pallet::call_index(8)]
+        pub fn reservar_horario(
+            origin: OriginFor<T>,
+            profissional: T::AccountId,
+            horario: u32,
+            data: String,
+        ) -> DispatchResult {
+            let cliente = ensure_signed(origin)?;
+            Self::registrar_escrita(&cliente)?;
+            ensure!(cliente != profissional, Error::<T>::ReservaPropria);
+            let modelo = Horarios::<T>::get(&profissional, horario).ok_or(Error::<T>::HorarioNaoEncontrado)?;
+            let data_parsed = Self::convert_to_timestamp(data.into_bytes())?;
+            ensure!(
+                Self::dia_da_semana(data_parsed) == modelo.dia_semana,
+                Error::<T>::DiaSemanaIncompativel
+            );
+
+            let inicio = data_parsed + Self::hora_em_minutos(&modelo.hora)? as u64 * 60;
+            let fim = inicio + modelo.duracao as u64 * 60;
+            ensure!(inicio > Self::agora(), Error::<T>::HorarioNoPassado);
+
+            let mut reservas = Reservas::<T>::get((&profissional, horario, data_parsed));
+            ensure!(
+                !reservas.iter().any(|reserva| reserva.cliente == cliente),
+                Error::<T>::ReservaDuplicada
+            );
+            ensure!(
+                (reservas.len() as u32) < modelo.capacidade,
+                Error::<T>::HorarioLotado
+            );
+            ensure!(
+                !Self::tem_conflito(&cliente, inicio, fim, None),
+                Error::<T>::ConflitoDeHorario
+            );
+            ensure!(
+                !Self::profissional_ocupado(&profissional, horario, data_parsed, inicio, fim),
+                Error::<T>::ProfissionalOcupado
+            );
+            if !modelo.deposito.is_zero() {
+                T::Moeda::hold(&HoldReason::DepositoReserva.into(), &cliente, modelo.deposito)
+                    .map_err(|_| Error::<T>::SaldoInsuficiente)?;
+            }
+
+            let compromisso_cliente = Self::inserir_compromisso(
+                &cliente,
+                modelo.titulo.clone(),
+                data_parsed,
+                modelo.hora.clone(),
+                Prioridade::Media,
+                modelo.duracao,
+            )?;
+            let compromisso_profissional = Self::inserir_compromisso(
+                &profissional,
+                modelo.titulo,
+                data_parsed,
+                modelo.hora,
+                Prioridade::Media,
+                modelo.duracao,
+            )?;
+
+            reservas
+                .try_push(Reserva {
+                    cliente: cliente.clone(),
+                    compromisso_cliente,
+                    compromisso_profissional,
+                    inicio,
+                    limite_cancelamento: inicio.saturating_sub(modelo.prazo_cancelamento as u64 * 60),
+                    deposito: modelo.deposito,
+                })
+                .map_err(|_| Error::<T>::HorarioLotado)?;
+            Reservas::<T>::insert((&profissional, horario, data_parsed), reservas);
+
+            let origem = (profissional.clone(), horario, data_parsed);
+            ReservaDoCompromisso::<T>::insert(&cliente, compromisso_cliente, origem.clone());
+            ReservaDoCompromisso::<T>::insert(&profissional, compromisso_profissional, origem);
+
+            Self::deposit_event(Event::HorarioReservado {
+                cliente,
+                profissional,
+                horario,
+                data: data_parsed,
+                compromisso_cliente,
+                compromisso_profissional,
+            });
+            Ok(())
+        }
+
+        // O cliente só pode cancelar até o prazo definido no horário; o profissional pode cancelar a qualquer momento.
+        #[pallet::weight(Pallet::<T>::peso_chamada(13, 13 + 2 * T::MaxContatosPorCompromisso::get() as u64))]
+        #[pallet::call_index(9)]
+        pub fn cancelar_reserva(
+            origin: OriginFor<T>,
+            profissional: T::AccountId,
+            horario: u32,
+            data: String,
+            cliente: T::AccountId,
+        ) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            ensure!(quem == cliente || quem == profissional, Error::<T>::SemPermissao);
+            let data_parsed = Self::convert_to_timestamp(data.into_bytes())?;
+
+            if quem != profissional {
+                let reserva = Reservas::<T>::get((&profissional, horario, data_parsed))
+                    .into_iter()
+                    .find(|reserva| reserva.cliente == cliente)
+                    .ok_or(Error::<T>::ReservaNaoEncontrada)?;
+                ensure!(
+                    Self::agora() <= reserva.limite_cancelamento,
+                    Error::<T>::PrazoCancelamentoExpirado
+                );
+            }
+
+            let reserva = Self::retirar_reserva(&profissional, horario, data_parsed, &cliente)?;
+            Self::remover_compromisso(&cliente, reserva.compromisso_cliente);
+            Self::remover_compromisso(&profissional, reserva.compromisso_profissional);
+            let deposito_devolvido = Self::liberar_deposito(&cliente, reserva.deposito)?;
+
+            Self::deposit_event(Event::ReservaCancelada {
+                cliente,
+                profissional,
+                horario,
+                data: data_parsed,
+                por: quem,
+                deposito_devolvido,
+            });
+            Ok(())
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(7, 7))]
+        #[pallet::call_index(10)]
+        pub fn confirmar_presenca(
+            origin: OriginFor<T>,
+            horario: u32,
+            data: String,
+            cliente: T::AccountId,
+        ) -> DispatchResult {
+            let profissional = ensure_signed(origin)?;
+            Self::registrar_escrita(&profissional)?;
+            let data_parsed = Self::convert_to_timestamp(data.into_bytes())?;
+            Self::ensure_reserva_iniciada(&profissional, horario, data_parsed, &cliente)?;
+
+            let reserva = Self::retirar_reserva(&profissional, horario, data_parsed, &cliente)?;
+            let deposito_devolvido = Self::liberar_deposito(&cliente, reserva.deposito)?;
+            for (dono, id) in [(&cliente, reserva.compromisso_cliente), (&profissional, reserva.compromisso_profissional)] {
+                Compromissos::<T>::mutate(dono, id, |compromisso| {
+                    if let Some(compromisso) = compromisso {
+                        compromisso.status = Status::Concluido;
+                    }
+                });
+            }
+
+            Self::deposit_event(Event::PresencaConfirmada {
+                cliente,
+                profissional,
+                horario,
+                data: data_parsed,
+                deposito_devolvido,
+            });
+            Ok(())
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(8, 8))]
+        #[pallet::call_index(11)]
+        pub fn registrar_falta(
+            origin: OriginFor<T>,
+            horario: u32,
+            data: String,
+            cliente: T::AccountId,
+        ) -> DispatchResult {
+            let profissional = ensure_signed(origin)?;
+            Self::registrar_escrita(&profissional)?;
+            let data_parsed = Self::convert_to_timestamp(data.into_bytes())?;
+            Self::ensure_reserva_iniciada(&profissional, horario, data_parsed, &cliente)?;
+
+            let reserva = Self::retirar_reserva(&profissional, horario, data_parsed, &cliente)?;
+            // A vaga não aconteceu: os dois compromissos deixam de contar como ativos
+            let motivo = BoundedVec::try_from(b"Falta".to_vec()).ok();
+            for (dono, id) in [(&cliente, reserva.compromisso_cliente), (&profissional, reserva.compromisso_profissional)] {
+                Compromissos::<T>::mutate(dono, id, |compromisso| {
+                    if let Some(compromisso) = compromisso {
+                        compromisso.status = Status::Cancelado;
+                        compromisso.motivo_cancelamento = motivo.clone();
+                    }
+                });
+            }
+            let deposito_transferido = if reserva.deposito.is_zero() {
+                reserva.deposito
+            } else {
+                T::Moeda::transfer_on_hold(
+                    &HoldReason::DepositoReserva.into(),
+                    &cliente,
+                    &profissional,
+                    reserva.deposito,
+                    Precision::BestEffort,
+                    Restriction::Free,
+                    Fortitude::Polite,
+                )?
             };
 
-            Contatos::<T>::insert(&quem, id, contato);
-            Self::deposit_event(Event::ContatoAtualizado { quem, id });
+            Self::deposit_event(Event::FaltaRegistrada {
+                cliente,
+                profissional,
+                horario,
+                data: data_parsed,
+                deposito_transferido,
+            });
             Ok(())
         }
 
-        #[pallet::weight(Weight::default())]
-        #[pallet::call_index(2)]
-        pub fn deletar_contato(origin: OriginFor<T>, id: u32) -> DispatchResult {
+        #[pallet::weight(Pallet::<T>::peso_chamada(0, 1))]
+        #[pallet::call_index(12)]
+        pub fn definir_privacidade(origin: OriginFor<T>, privacidade: Privacidade) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            Privacidades::<T>::insert(&quem, privacidade);
+            Self::deposit_event(Event::PrivacidadeDefinida { quem, privacidade });
+            Ok(())
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(1, 1))]
+        #[pallet::call_index(13)]
+        pub fn confirmar(origin: OriginFor<T>, id: u32) -> DispatchResult {
             let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            Self::mudar_status(&quem, id, Status::Confirmado)?;
+            Self::deposit_event(Event::CompromissoConfirmado { quem, id });
+            Ok(())
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(1, 1))]
+        #[pallet::call_index(14)]
+        pub fn concluir(origin: OriginFor<T>, id: u32) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            Self::mudar_status(&quem, id, Status::Concluido)?;
+            Self::deposit_event(Event::CompromissoConcluido { quem, id });
+            Ok(())
+        }
+
+        // Compromissos vindos de reservas são cancelados por `cancelar_reserva`, que também trata o depósito.
+        #[pallet::weight(Pallet::<T>::peso_chamada(3, 2))]
+        #[pallet::call_index(15)]
+        pub fn cancelar(origin: OriginFor<T>, id: u32, motivo: Option<String>) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
             ensure!(
-                Contatos::<T>::contains_key(&quem, id),
-                Error::<T>::ContatoNaoEncontrado
+                !ReservaDoCompromisso::<T>::contains_key(&quem, id),
+                Error::<T>::CompromissoReservado
             );
-            Contatos::<T>::remove(&quem, id);
-            Self::deposit_event(Event::ContatoDeletado { quem, id });
+            let motivo: Option<BoundedVec<u8, T::MaxMotivoLength>> = motivo
+                .map(|motivo| BoundedVec::try_from(motivo.into_bytes()).map_err(|_| Error::<T>::MotivoMuitoLongo))
+                .transpose()?;
+
+            Self::mudar_status(&quem, id, Status::Cancelado)?;
+            Compromissos::<T>::mutate(&quem, id, |compromisso| {
+                if let Some(compromisso) = compromisso {
+                    compromisso.motivo_cancelamento = motivo.clone();
+                }
+            });
+            Self::deposit_event(Event::CompromissoCancelado { quem, id, motivo });
             Ok(())
         }
 
-        #[pallet::weight(Weight::default())]
-        #[pallet::call_index(3)]
-        pub fn criar_compromisso(
+        // Feriados, férias, congressos: sem hora marcada e podendo durar vários dias
+        #[pallet::weight(Pallet::<T>::peso_chamada(4, 4))]
+        #[pallet::call_index(16)]
+        pub fn criar_compromisso_dia_inteiro(
             origin: OriginFor<T>,
             titulo: String,
             data: String,
-            hora: String,
+            data_fim: Option<String>,
             prioridade: Prioridade,
-            duracao: u32,
         ) -> DispatchResult {
             let quem = ensure_signed(origin)?;
-            let id = ContadorCompromissos::<T>::get(&quem);
+            Self::registrar_escrita(&quem)?;
             let data_parsed = Self::convert_to_timestamp(data.into_bytes())?;
+            let data_fim_parsed = match data_fim {
+                Some(data_fim) => Self::convert_to_timestamp(data_fim.into_bytes())?,
+                None => data_parsed,
+            };
+            ensure!(data_fim_parsed >= data_parsed, Error::<T>::DataFimInvalida);
+            let dias = (data_fim_parsed - data_parsed) / 86400 + 1;
+            let duracao = (dias * 24 * 60).try_into().map_err(|_| Error::<T>::DataFimInvalida)?;
+            let titulo = BoundedVec::try_from(titulo.into_bytes()).map_err(|_| Error::<T>::TituloMuitoLongo)?;
+            let id = Self::alocar_id_compromisso(&quem)?;
+
+            let compromisso = Compromisso {
+                id,
+                titulo,
+                data: data_parsed,
+                hora: BoundedVec::default(),
+                prioridade,
+                duracao,
+                status: Status::Agendado,
+                motivo_cancelamento: None,
+                dia_inteiro: true,
+                data_fim: data_fim_parsed,
+                local: None,
+                link: None,
+                contatos: BoundedVec::default(),
+                anexos: BoundedVec::default(),
+                notas: BoundedVec::default(),
+                lembretes: BoundedVec::default(),
+            };
+
+            Compromissos::<T>::insert(&quem, id, compromisso);
+            let compromisso = Self::detalhe(|| Compromissos::<T>::get(&quem, id));
+            Self::deposit_event(Event::CompromissoCriado { quem, id, compromisso });
+            Ok(())
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(1, 1))]
+        #[pallet::call_index(17)]
+        pub fn definir_local(
+            origin: OriginFor<T>,
+            id: u32,
+            local: Option<Local<T>>,
+            link: Option<String>,
+        ) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            if let Some(local) = &local {
+                Self::validate_local_input(local)?;
+            }
+            let link: Option<BoundedVec<u8, T::MaxLinkLength>> = link
+                .map(|link| {
+                    Self::validate_link_input(link.as_bytes())?;
+                    BoundedVec::try_from(link.into_bytes()).map_err(|_| Error::<T>::LinkMuitoLongo)
+                })
+                .transpose()?;
+
+            Compromissos::<T>::try_mutate(&quem, id, |compromisso| -> DispatchResult {
+                let compromisso = compromisso.as_mut().ok_or(Error::<T>::CompromissoNaoEncontrado)?;
+                compromisso.local = local.clone();
+                compromisso.link = link.clone();
+                Ok(())
+            })?;
+            Self::deposit_event(Event::LocalDefinido { quem, id, local, link });
+            Ok(())
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(1 + T::MaxContatosPorCompromisso::get() as u64 * (1 + T::MaxCompromissosPorContato::get() as u64), 1 + 2 * T::MaxContatosPorCompromisso::get() as u64))]
+        #[pallet::call_index(18)]
+        pub fn vincular_contatos(origin: OriginFor<T>, id: u32, contatos: Vec<u32>) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            let contatos = Self::validar_contatos(&quem, contatos)?;
+
+            Compromissos::<T>::try_mutate(&quem, id, |compromisso| -> DispatchResult {
+                let compromisso = compromisso.as_mut().ok_or(Error::<T>::CompromissoNaoEncontrado)?;
+                let novos = contatos.iter().filter(|contato| !compromisso.contatos.contains(contato));
+                Self::ensure_vinculos_livres(&quem, novos)?;
+                for contato in compromisso.contatos.iter() {
+                    CompromissosDoContato::<T>::remove((&quem, contato, id));
+                }
+                for contato in contatos.iter() {
+                    CompromissosDoContato::<T>::insert((&quem, contato, id), ());
+                }
+                compromisso.contatos = contatos.clone();
+                Ok(())
+            })?;
+            Self::deposit_event(Event::ContatosVinculados { quem, id, contatos });
+            Ok(())
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(2, 2))]
+        #[pallet::call_index(19)]
+        pub fn criar_tarefa(
+            origin: OriginFor<T>,
+            titulo: String,
+            prazo: Option<String>,
+            prioridade: Prioridade,
+            checklist: Vec<String>,
+        ) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            let id = ContadorTarefas::<T>::get(&quem);
+            let prazo_parsed = prazo.map(|prazo| Self::convert_to_timestamp(prazo.into_bytes())).transpose()?;
+
+            let tarefa = Tarefa {
+                id,
+                titulo: BoundedVec::try_from(titulo.into_bytes()).map_err(|_| Error::<T>::TituloMuitoLongo)?,
+                prazo: prazo_parsed,
+                prioridade,
+                concluida: false,
+                checklist: Self::montar_checklist(checklist.into_iter().map(|item| (item, false)).collect())?,
+            };
+
+            Tarefas::<T>::insert(&quem, id, tarefa);
+            ContadorTarefas::<T>::insert(&quem, id.checked_add(1).ok_or(Error::<T>::LimiteAtingido)?);
+            let tarefa = Self::detalhe(|| Tarefas::<T>::get(&quem, id));
+            Self::deposit_event(Event::TarefaCriada { quem, id, tarefa });
+            Ok(())
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(2, 1))]
+        #[pallet::call_index(20)]
+        pub fn atualizar_tarefa(
+            origin: OriginFor<T>,
+            id: u32,
+            titulo: String,
+            prazo: Option<String>,
+            prioridade: Prioridade,
+            concluida: bool,
+            checklist: Vec<(String, bool)>,
+        ) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            let prazo_parsed = prazo.map(|prazo| Self::convert_to_timestamp(prazo.into_bytes())).transpose()?;
             ensure!(
-                Self::validate_hour_input(hora.clone().into_bytes()).is_ok(),
-                Error::<T>::HoraInvalida
+                Tarefas::<T>::contains_key(&quem, id),
+                Error::<T>::TarefaNaoEncontrada
+            );
+
+            let tarefa = Tarefa {
+                id,
+                titulo: BoundedVec::try_from(titulo.into_bytes()).map_err(|_| Error::<T>::TituloMuitoLongo)?,
+                prazo: prazo_parsed,
+                prioridade,
+                concluida,
+                checklist: Self::montar_checklist(checklist)?,
+            };
+
+            Tarefas::<T>::insert(&quem, id, tarefa);
+            let tarefa = Self::detalhe(|| Tarefas::<T>::get(&quem, id));
+            Self::deposit_event(Event::TarefaAtualizada { quem, id, tarefa });
+            Ok(())
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(2, 1))]
+        #[pallet::call_index(21)]
+        pub fn deletar_tarefa(origin: OriginFor<T>, id: u32) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            ensure!(
+                Tarefas::<T>::contains_key(&quem, id),
+                Error::<T>::TarefaNaoEncontrada
+            );
+
+            let tarefa = Self::detalhe(|| Tarefas::<T>::get(&quem, id));
+            Tarefas::<T>::remove(&quem, id);
+            Self::deposit_event(Event::TarefaDeletada { quem, id, tarefa });
+            Ok(())
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(2, 1))]
+        #[pallet::call_index(22)]
+        pub fn marcar_item_checklist(origin: OriginFor<T>, id: u32, item: u32, feito: bool) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            Tarefas::<T>::try_mutate(&quem, id, |tarefa| -> DispatchResult {
+                let tarefa = tarefa.as_mut().ok_or(Error::<T>::TarefaNaoEncontrada)?;
+                let item = tarefa.checklist.get_mut(item as usize).ok_or(Error::<T>::ItemNaoEncontrado)?;
+                item.feito = feito;
+                Ok(())
+            })?;
+            let tarefa = Self::detalhe(|| Tarefas::<T>::get(&quem, id));
+            Self::deposit_event(Event::TarefaAtualizada { quem, id, tarefa });
+            Ok(())
+        }
+
+        // Com `dados`, o conteúdo é conferido contra o hash e gravado pela indexação offchain,
+        // ficando disponível nos nós que rodam com `--enable-offchain-indexing true`.
+        #[pallet::weight(Pallet::<T>::peso_anexo(dados.as_ref().map_or(0, |dados| dados.len())))]
+        #[pallet::call_index(23)]
+        pub fn anexar(
+            origin: OriginFor<T>,
+            alvo: AlvoAnexo,
+            hash: [u8; 32],
+            tamanho: u64,
+            mime: String,
+            localizador: Option<String>,
+            dados: Option<BoundedVec<u8, T::MaxDadosAnexo>>,
+        ) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            Self::validate_mime_input(mime.as_bytes())?;
+            if let Some(dados) = &dados {
+                ensure!(
+                    dados.len() as u64 == tamanho && sp_io::hashing::blake2_256(dados) == hash,
+                    Error::<T>::ConteudoDivergente
+                );
+            }
+
+            let anexo = Anexo {
+                hash,
+                tamanho,
+                mime: BoundedVec::try_from(mime.into_bytes()).map_err(|_| Error::<T>::MimeInvalido)?,
+                localizador: localizador
+                    .map(|localizador| {
+                        BoundedVec::try_from(localizador.into_bytes()).map_err(|_| Error::<T>::LocalizadorMuitoLongo)
+                    })
+                    .transpose()?,
+            };
+            Self::alterar_anexos(&quem, alvo, |anexos| {
+                ensure!(!anexos.iter().any(|anexo| anexo.hash == hash), Error::<T>::AnexoDuplicado);
+                anexos.try_push(anexo).map_err(|_| Error::<T>::MuitosAnexos)
+            })?;
+
+            let indexado = dados.is_some();
+            if let Some(dados) = dados {
+                sp_io::offchain_index::set(&chave_anexo(&hash), &dados);
+            }
+            Self::deposit_event(Event::AnexoAdicionado { quem, alvo, hash, indexado });
+            Ok(())
+        }
+
+        // O conteúdo indexado não é apagado: outro registro pode referenciar o mesmo hash.
+        #[pallet::weight(Pallet::<T>::peso_chamada(1, 1))]
+        #[pallet::call_index(24)]
+        pub fn remover_anexo(origin: OriginFor<T>, alvo: AlvoAnexo, hash: [u8; 32]) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            Self::alterar_anexos(&quem, alvo, |anexos| {
+                let antes = anexos.len();
+                anexos.retain(|anexo| anexo.hash != hash);
+                ensure!(anexos.len() < antes, Error::<T>::AnexoNaoEncontrado);
+                Ok(())
+            })?;
+            Self::deposit_event(Event::AnexoRemovido { quem, alvo, hash });
+            Ok(())
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(2, 1))]
+        #[pallet::call_index(25)]
+        pub fn atualizar_notas_contato(origin: OriginFor<T>, id: u32, notas: String) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            let notas: BoundedVec<u8, T::MaxNotasLength> =
+                BoundedVec::try_from(notas.into_bytes()).map_err(|_| Error::<T>::NotasMuitoLongas)?;
+
+            Contatos::<T>::try_mutate(&quem, id, |contato| -> DispatchResult {
+                let contato = contato.as_mut().ok_or(Error::<T>::ContatoNaoEncontrado)?;
+                contato.notas = notas;
+                Self::indexar_texto_contato(&quem, contato);
+                Ok(())
+            })?;
+            let contato = Self::detalhe(|| Contatos::<T>::get(&quem, id));
+            Self::deposit_event(Event::ContatoAtualizado { quem, id, contato });
+            Ok(())
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(2, 1))]
+        #[pallet::call_index(26)]
+        pub fn atualizar_notas_compromisso(origin: OriginFor<T>, id: u32, notas: String) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            let notas: BoundedVec<u8, T::MaxNotasLength> =
+                BoundedVec::try_from(notas.into_bytes()).map_err(|_| Error::<T>::NotasMuitoLongas)?;
+
+            Compromissos::<T>::try_mutate(&quem, id, |compromisso| -> DispatchResult {
+                compromisso.as_mut().ok_or(Error::<T>::CompromissoNaoEncontrado)?.notas = notas;
+                Ok(())
+            })?;
+            let compromisso = Self::detalhe(|| Compromissos::<T>::get(&quem, id));
+            Self::deposit_event(Event::CompromissoAtualizado { quem, id, compromisso });
+            Ok(())
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(1 + T::MaxContatosPorCompromisso::get() as u64 + T::MaxModelosPorConta::get() as u64, 2))]
+        #[pallet::call_index(27)]
+        pub fn criar_modelo(
+            origin: OriginFor<T>,
+            titulo: String,
+            duracao: u32,
+            prioridade: Prioridade,
+            lembretes: Vec<u32>,
+            participantes: Vec<u32>,
+        ) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            let id = ContadorModelos::<T>::get(&quem);
+            ensure!(
+                (Modelos::<T>::iter_key_prefix(&quem).count() as u32) < T::MaxModelosPorConta::get(),
+                Error::<T>::LimiteAtingido
             );
             ensure!(
                 Self::validate_duration_input(duracao.into()).is_ok(),
                 Error::<T>::DuracaoInvalida
             );
 
-            let compromisso = Compromisso {
+            let modelo = Modelo {
                 id,
                 titulo: BoundedVec::try_from(titulo.into_bytes()).map_err(|_| Error::<T>::TituloMuitoLongo)?,
-                data: data_parsed,
-                hora: BoundedVec::try_from(hora.into_bytes()).map_err(|_| Error::<T>::HoraInvalida)?,
-                prioridade,
                 duracao,
+                prioridade,
+                lembretes: Self::montar_lembretes(lembretes)?,
+                participantes: Self::validar_contatos(&quem, participantes)?,
             };
 
-            Compromissos::<T>::insert(&quem, id, compromisso);
-            ContadorCompromissos::<T>::insert(&quem, id + 1);
-            Self::deposit_event(Event::CompromissoCriado { quem, id });
+            Modelos::<T>::insert(&quem, id, modelo);
+            ContadorModelos::<T>::insert(&quem, id.checked_add(1).ok_or(Error::<T>::LimiteAtingido)?);
+            Self::deposit_event(Event::ModeloCriado { quem, id });
             Ok(())
         }
 
-        #[pallet::weight(Weight::default())]
-        #[pallet::call_index(4)]
-        pub fn atualizar_compromisso(
+        #[pallet::weight(Pallet::<T>::peso_chamada(1 + T::MaxContatosPorCompromisso::get() as u64, 1))]
+        #[pallet::call_index(28)]
+        pub fn atualizar_modelo(
             origin: OriginFor<T>,
             id: u32,
             titulo: String,
-            data: String,
-            hora: String,
-            prioridade: Prioridade,
             duracao: u32,
+            prioridade: Prioridade,
+            lembretes: Vec<u32>,
+            participantes: Vec<u32>,
         ) -> DispatchResult {
             let quem = ensure_signed(origin)?;
-            let data_parsed = Self::convert_to_timestamp(data.into_bytes())?;
+            Self::registrar_escrita(&quem)?;
             ensure!(
-                Compromissos::<T>::contains_key(&quem, id),
-                Error::<T>::CompromissoNaoEncontrado
+                Modelos::<T>::contains_key(&quem, id),
+                Error::<T>::ModeloNaoEncontrado
+            );
+            ensure!(
+                Self::validate_duration_input(duracao.into()).is_ok(),
+                Error::<T>::DuracaoInvalida
+            );
+
+            let modelo = Modelo {
+                id,
+                titulo: BoundedVec::try_from(titulo.into_bytes()).map_err(|_| Error::<T>::TituloMuitoLongo)?,
+                duracao,
+                prioridade,
+                lembretes: Self::montar_lembretes(lembretes)?,
+                participantes: Self::validar_contatos(&quem, participantes)?,
+            };
+
+            Modelos::<T>::insert(&quem, id, modelo);
+            Self::deposit_event(Event::ModeloAtualizado { quem, id });
+            Ok(())
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(1, 1))]
+        #[pallet::call_index(29)]
+        pub fn deletar_modelo(origin: OriginFor<T>, id: u32) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            ensure!(
+                Modelos::<T>::contains_key(&quem, id),
+                Error::<T>::ModeloNaoEncontrado
             );
+            Modelos::<T>::remove(&quem, id);
+            Self::deposit_event(Event::ModeloDeletado { quem, id });
+            Ok(())
+        }
+
+        // Cria um compromisso com os valores do modelo; título, duração e prioridade podem ser sobrescritos
+        #[pallet::weight(Pallet::<T>::peso_chamada(5 + T::MaxContatosPorCompromisso::get() as u64 * T::MaxCompromissosPorContato::get() as u64, 4 + T::MaxContatosPorCompromisso::get() as u64))]
+        #[pallet::call_index(30)]
+        pub fn criar_de_modelo(
+            origin: OriginFor<T>,
+            modelo: u32,
+            data: String,
+            hora: String,
+            titulo: Option<String>,
+            duracao: Option<u32>,
+            prioridade: Option<Prioridade>,
+        ) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            let base = Modelos::<T>::get(&quem, modelo).ok_or(Error::<T>::ModeloNaoEncontrado)?;
+            let data_parsed = Self::convert_to_timestamp(data.into_bytes())?;
             ensure!(
                 Self::validate_hour_input(hora.clone().into_bytes()).is_ok(),
                 Error::<T>::HoraInvalida
             );
+            let duracao = duracao.unwrap_or(base.duracao);
             ensure!(
                 Self::validate_duration_input(duracao.into()).is_ok(),
                 Error::<T>::DuracaoInvalida
             );
+            let titulo = match titulo {
+                Some(titulo) => BoundedVec::try_from(titulo.into_bytes()).map_err(|_| Error::<T>::TituloMuitoLongo)?,
+                None => base.titulo,
+            };
+            let data_fim = Self::dia_final(data_parsed, hora.as_bytes(), duracao);
+            let hora = BoundedVec::try_from(hora.into_bytes()).map_err(|_| Error::<T>::HoraInvalida)?;
+            let id = Self::alocar_id_compromisso(&quem)?;
 
             let compromisso = Compromisso {
                 id,
-                titulo: BoundedVec::try_from(titulo.into_bytes()).map_err(|_| Error::<T>::TituloMuitoLongo)?,
+                titulo,
                 data: data_parsed,
-                hora: BoundedVec::try_from(hora.into_bytes()).map_err(|_| Error::<T>::HoraInvalida)?,
-                prioridade,
+                data_fim,
+                hora,
+                prioridade: prioridade.unwrap_or(base.prioridade),
                 duracao,
+                status: Status::Agendado,
+                motivo_cancelamento: None,
+                dia_inteiro: false,
+                local: None,
+                link: None,
+                contatos: base.participantes,
+                anexos: BoundedVec::default(),
+                notas: BoundedVec::default(),
+                lembretes: base.lembretes,
             };
 
+            Self::ensure_vinculos_livres(&quem, compromisso.contatos.iter())?;
+            for contato in compromisso.contatos.iter() {
+                CompromissosDoContato::<T>::insert((&quem, contato, id), ());
+            }
             Compromissos::<T>::insert(&quem, id, compromisso);
-            Self::deposit_event(Event::CompromissoAtualizado { quem, id });
+            let compromisso = Self::detalhe(|| Compromissos::<T>::get(&quem, id));
+            Self::deposit_event(Event::CompromissoCriadoDeModelo { quem, id, modelo, compromisso });
             Ok(())
         }
 
-        #[pallet::weight(Weight::default())]
-        #[pallet::call_index(5)]
-        pub fn deletar_compromisso(origin: OriginFor<T>, id: u32) -> DispatchResult {
+        // Muda só a data e a hora, mantendo duração e demais campos. Os lembretes são relativos
+        // ao início e são recalculados para o novo horário; os que já teriam passado são descartados.
+        // Compromissos de dia inteiro não têm hora e são deslocados inteiros para a nova data.
+        // O peso cobre a checagem de conflitos, que lê todos os compromissos da conta.
+        #[pallet::weight(Pallet::<T>::peso_chamada(4 + T::MaxCompromissosPorConta::get() as u64, 2))]
+        #[pallet::call_index(31)]
+        pub fn reagendar(origin: OriginFor<T>, id: u32, data: String, hora: Option<String>) -> DispatchResult {
             let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            let data_parsed = Self::convert_to_timestamp(data.into_bytes())?;
+            let mut compromisso = Compromissos::<T>::get(&quem, id).ok_or(Error::<T>::CompromissoNaoEncontrado)?;
             ensure!(
-                Compromissos::<T>::contains_key(&quem, id),
-                Error::<T>::CompromissoNaoEncontrado
+                !ReservaDoCompromisso::<T>::contains_key(&quem, id),
+                Error::<T>::CompromissoReservado
+            );
+            ensure!(
+                matches!(compromisso.status, Status::Agendado | Status::Confirmado),
+                Error::<T>::CompromissoEncerrado
+            );
+            let (de_inicio, de_fim) = Self::intervalo_compromisso(&compromisso);
+
+            if compromisso.dia_inteiro {
+                ensure!(hora.is_none(), Error::<T>::HoraInvalida);
+                compromisso.data_fim = data_parsed + (compromisso.data_fim - compromisso.data);
+            } else {
+                let hora = hora.ok_or(Error::<T>::HoraInvalida)?;
+                ensure!(
+                    Self::validate_hour_input(hora.clone().into_bytes()).is_ok(),
+                    Error::<T>::HoraInvalida
+                );
+                compromisso.data_fim = Self::dia_final(data_parsed, hora.as_bytes(), compromisso.duracao);
+                compromisso.hora = BoundedVec::try_from(hora.into_bytes()).map_err(|_| Error::<T>::HoraInvalida)?;
+            }
+            compromisso.data = data_parsed;
+
+            let (inicio, fim) = Self::intervalo_compromisso(&compromisso);
+            ensure!(
+                !Self::tem_conflito(&quem, inicio, fim, Some(id)),
+                Error::<T>::ConflitoDeHorario
             );
 
-            Compromissos::<T>::remove(&quem, id);
-            Self::deposit_event(Event::CompromissoDeletado { quem, id });
+            let de = Intervalo { inicio: de_inicio, fim: de_fim };
+            let para = Intervalo { inicio, fim };
+            HistoricoReagendamentos::<T>::mutate(&quem, id, |historico| {
+                if historico.is_full() && !historico.is_empty() {
+                    historico.remove(0);
+                }
+                let _ = historico.try_push(de);
+            });
+            let participantes = compromisso.contatos.clone();
+            let lembretes = Self::lembretes_pendentes(&compromisso, Self::agora());
+            Compromissos::<T>::insert(&quem, id, compromisso);
+            Self::deposit_event(Event::CompromissoReagendado { quem, id, de, para, participantes, lembretes });
+            Ok(())
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_chamada(0, 1))]
+        #[pallet::call_index(32)]
+        pub fn definir_retencao(origin: OriginFor<T>, retencao: Retencao) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::registrar_escrita(&quem)?;
+            Retencoes::<T>::insert(&quem, retencao);
+            Self::deposit_event(Event::RetencaoDefinida { quem, retencao });
             Ok(())
         }
+
+        // Lotes: uma escrita da cota por item, um evento por item e `LoteConcluido` no fim,
+        // ou o erro do item que falhou no modo `TudoOuNada`. Os pesos cobrem o pior caso de cada item.
+        #[pallet::weight(Pallet::<T>::peso_lote(itens.len(), 5, 5 + Pallet::<T>::max_prefixos_contato()))]
+        #[pallet::call_index(33)]
+        pub fn criar_contatos(
+            origin: OriginFor<T>,
+            itens: BoundedVec<NovoContato, T::MaxLote>,
+            modo: ModoLote,
+        ) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::processar_lote(&quem, itens.into_inner(), modo, |novo| {
+                Self::do_criar_contato(&quem, novo).map(|_| ())
+            })
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_lote(itens.len(), 2, 1 + 2 * Pallet::<T>::max_prefixos_contato()))]
+        #[pallet::call_index(34)]
+        pub fn atualizar_contatos(
+            origin: OriginFor<T>,
+            itens: BoundedVec<(u32, NovoContato), T::MaxLote>,
+            modo: ModoLote,
+        ) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::processar_lote(&quem, itens.into_inner(), modo, |(id, novo)| {
+                Self::do_atualizar_contato(&quem, id, novo)
+            })
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_deletar_contatos(ids.len()))]
+        #[pallet::call_index(35)]
+        pub fn deletar_contatos(origin: OriginFor<T>, ids: BoundedVec<u32, T::MaxLote>, modo: ModoLote) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::processar_lote(&quem, ids.into_inner(), modo, |id| Self::do_deletar_contato(&quem, id))
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_lote(itens.len(), 4, 4))]
+        #[pallet::call_index(36)]
+        pub fn criar_compromissos(
+            origin: OriginFor<T>,
+            itens: BoundedVec<NovoCompromisso, T::MaxLote>,
+            modo: ModoLote,
+        ) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::processar_lote(&quem, itens.into_inner(), modo, |novo| {
+                Self::do_criar_compromisso(&quem, novo).map(|_| ())
+            })
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_lote(itens.len(), 3, 1))]
+        #[pallet::call_index(37)]
+        pub fn atualizar_compromissos(
+            origin: OriginFor<T>,
+            itens: BoundedVec<(u32, NovoCompromisso), T::MaxLote>,
+            modo: ModoLote,
+        ) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::processar_lote(&quem, itens.into_inner(), modo, |(id, novo)| {
+                Self::do_atualizar_compromisso(&quem, id, novo)
+            })
+        }
+
+        #[pallet::weight(Pallet::<T>::peso_lote(
+            ids.len(),
+            7,
+            4 + T::MaxContatosPorCompromisso::get() as u64,
+        ))]
+        #[pallet::call_index(38)]
+        pub fn deletar_compromissos(
+            origin: OriginFor<T>,
+            ids: BoundedVec<u32, T::MaxLote>,
+            modo: ModoLote,
+        ) -> DispatchResult {
+            let quem = ensure_signed(origin)?;
+            Self::processar_lote(&quem, ids.into_inner(), modo, |id| Self::do_deletar_compromisso(&quem, id))
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,330 @@
+// Cada versão é uma `SteppedMigration` executada pelo `pallet_migrations` em vários blocos: cada passo
+// processa itens enquanto houver peso no `WeightMeter` e devolve um cursor com a última chave tratada.
+// O runtime lista as migrações, em ordem, no `Migrations` do `pallet_migrations` e aponta o
+// `MultiBlockMigrator` do `frame_system` para ele, que bloqueia as extrínsecas até o fim da migração.
+//
+// As versões 1 a 3 saem juntas no primeiro runtime com versão de storage (spec_version 101), então
+// nenhuma chain fica parada no layout intermediário de uma delas: a v1 traduz o layout anterior direto
+// para o atual, já com os campos que chegaram depois dela (contatos, anexos, notas e lembretes).
+// Depois desse runtime, cada mudança de layout precisa de uma versão nova com o seu próprio passo.
+
+use crate::{Config, Pallet};
+use frame_support::{
+    migrations::{MigrationId, SteppedMigrationError},
+    pallet_prelude::*,
+    traits::GetStorageVersion,
+    weights::WeightMeter,
+};
+
+const PALLET_ID: [u8; 14] = *b"gcc1919-agenda";
+
+fn id_migracao(de: u8, para: u8) -> MigrationId<14> {
+    MigrationId { pallet_id: PALLET_ID, version_from: de, version_to: para }
+}
+
+// Cursor das migrações que percorrem os contatos e depois os compromissos, com a última chave tratada
+#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, MaxEncodedLen)]
+pub enum CursorFases<A> {
+    Contatos(Option<(A, u32)>),
+    Compromissos(Option<(A, u32)>),
+}
+
+// Sem cursor a migração ainda não começou: só começa se o storage estiver na versão de origem.
+// Chains criadas já na versão atual (ou que rodaram a migração antes) não têm nada a fazer.
+fn iniciar<T: Config, C>(cursor: Option<C>, origem: u16, inicio: C) -> Option<C> {
+    match cursor {
+        Some(cursor) => Some(cursor),
+        None if Pallet::<T>::on_chain_storage_version() == origem => Some(inicio),
+        None => None,
+    }
+}
+
+// Chama `passo` enquanto couber mais um item no `meter`. `passo` devolve o próximo cursor, ou `None`
+// quando a migração terminou. Sem peso para ao menos um item o passo inteiro é recusado.
+fn em_passos<C>(
+    mut cursor: C,
+    peso_item: Weight,
+    meter: &mut WeightMeter,
+    mut passo: impl FnMut(C) -> Option<C>,
+) -> Result<Option<C>, SteppedMigrationError> {
+    if meter.remaining().any_lt(peso_item) {
+        return Err(SteppedMigrationError::InsufficientWeight { required: peso_item });
+    }
+    while meter.try_consume(peso_item).is_ok() {
+        match passo(cursor) {
+            Some(proximo) => cursor = proximo,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(cursor))
+}
+
+pub mod v1 {
+    use super::{em_passos, id_migracao, iniciar, CursorFases};
+    use crate::{Categoria, Compromisso, Compromissos, Config, Contato, Contatos, Pallet, Prioridade, Status};
+    use core::marker::PhantomData;
+    use frame_support::{
+        migrations::{MigrationId, SteppedMigration, SteppedMigrationError},
+        pallet_prelude::*,
+        weights::WeightMeter,
+    };
+
+    // Layout de `Contato` antes do controle de versão do storage
+    #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct ContatoV0<T: Config> {
+        pub id: u32,
+        pub nome: BoundedVec<u8, T::MaxNomeLength>,
+        pub telefone: BoundedVec<u8, T::MaxTelefoneLength>,
+        pub email: BoundedVec<u8, T::MaxEmailLength>,
+        pub idade: u32,
+        pub data_aniversario: u64,
+        pub categoria: Categoria,
+    }
+
+    impl<T: Config> ContatoV0<T> {
+        pub fn migrar(self) -> Contato<T> {
+            Contato {
+                id: self.id,
+                nome: self.nome,
+                telefone: self.telefone,
+                email: self.email,
+                idade: self.idade,
+                data_aniversario: self.data_aniversario,
+                categoria: self.categoria,
+                anexos: BoundedVec::default(),
+                notas: BoundedVec::default(),
+            }
+        }
+    }
+
+    // Layout de `Compromisso` antes do controle de versão do storage
+    #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct CompromissoV0<T: Config> {
+        pub id: u32,
+        pub titulo: BoundedVec<u8, T::MaxTituloLength>,
+        pub data: u64,
+        pub hora: BoundedVec<u8, T::MaxHoraLength>,
+        pub prioridade: Prioridade,
+        pub duracao: u32,
+    }
+
+    impl<T: Config> CompromissoV0<T> {
+        pub fn migrar(self) -> Compromisso<T> {
+            let data_fim = Pallet::<T>::dia_final(self.data, &self.hora, self.duracao);
+            Compromisso {
+                id: self.id,
+                titulo: self.titulo,
+                data: self.data,
+                hora: self.hora,
+                prioridade: self.prioridade,
+                duracao: self.duracao,
+                status: Status::Agendado,
+                motivo_cancelamento: None,
+                dia_inteiro: false,
+                data_fim,
+                local: None,
+                link: None,
+                contatos: BoundedVec::default(),
+                anexos: BoundedVec::default(),
+                notas: BoundedVec::default(),
+                lembretes: BoundedVec::default(),
+            }
+        }
+    }
+
+    // Os mapas com os valores ainda no layout antigo, lidos pela migração
+    pub mod v0 {
+        use super::{CompromissoV0, ContatoV0};
+        use crate::{Config, Pallet};
+        use frame_support::{storage_alias, Blake2_128Concat};
+
+        #[storage_alias]
+        pub type Contatos<T: Config> = StorageDoubleMap<
+            Pallet<T>,
+            Blake2_128Concat,
+            <T as frame_system::Config>::AccountId,
+            Blake2_128Concat,
+            u32,
+            ContatoV0<T>,
+        >;
+
+        #[storage_alias]
+        pub type Compromissos<T: Config> = StorageDoubleMap<
+            Pallet<T>,
+            Blake2_128Concat,
+            <T as frame_system::Config>::AccountId,
+            Blake2_128Concat,
+            u32,
+            CompromissoV0<T>,
+        >;
+    }
+
+    // Traduz os contatos e depois os compromissos para o layout atual, um item por vez
+    pub struct MigrarParaV1<T>(PhantomData<T>);
+
+    impl<T: Config> SteppedMigration for MigrarParaV1<T> {
+        type Cursor = CursorFases<T::AccountId>;
+        type Identifier = MigrationId<14>;
+
+        fn id() -> Self::Identifier {
+            id_migracao(0, 1)
+        }
+
+        fn step(
+            cursor: Option<Self::Cursor>,
+            meter: &mut WeightMeter,
+        ) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+            let Some(cursor) = iniciar::<T, _>(cursor, 0, CursorFases::Contatos(None)) else {
+                return Ok(None);
+            };
+            em_passos(cursor, T::DbWeight::get().reads_writes(1, 1), meter, |cursor| match cursor {
+                CursorFases::Contatos(ultimo) => {
+                    let proximo = match ultimo {
+                        Some((quem, id)) => v0::Contatos::<T>::iter_from(v0::Contatos::<T>::hashed_key_for(&quem, id)).next(),
+                        None => v0::Contatos::<T>::iter().next(),
+                    };
+                    Some(match proximo {
+                        Some((quem, id, antigo)) => {
+                            Contatos::<T>::insert(&quem, id, antigo.migrar());
+                            CursorFases::Contatos(Some((quem, id)))
+                        }
+                        None => CursorFases::Compromissos(None),
+                    })
+                }
+                CursorFases::Compromissos(ultimo) => {
+                    let proximo = match ultimo {
+                        Some((quem, id)) => {
+                            v0::Compromissos::<T>::iter_from(v0::Compromissos::<T>::hashed_key_for(&quem, id)).next()
+                        }
+                        None => v0::Compromissos::<T>::iter().next(),
+                    };
+                    match proximo {
+                        Some((quem, id, antigo)) => {
+                            Compromissos::<T>::insert(&quem, id, antigo.migrar());
+                            Some(CursorFases::Compromissos(Some((quem, id))))
+                        }
+                        None => {
+                            StorageVersion::new(1).put::<Pallet<T>>();
+                            None
+                        }
+                    }
+                }
+            })
+        }
+    }
+}
+
+pub mod v2 {
+    use super::{em_passos, id_migracao, iniciar, CursorFases};
+    use crate::{Compromissos, Config, Contatos, Pallet, TotalCompromissos, TotalContatos};
+    use core::marker::PhantomData;
+    use frame_support::{
+        migrations::{MigrationId, SteppedMigration, SteppedMigrationError},
+        pallet_prelude::*,
+        weights::WeightMeter,
+    };
+
+    // Preenche os totais de itens vivos por conta usados pelos limites da v2
+    pub struct MigrarParaV2<T>(PhantomData<T>);
+
+    impl<T: Config> SteppedMigration for MigrarParaV2<T> {
+        type Cursor = CursorFases<T::AccountId>;
+        type Identifier = MigrationId<14>;
+
+        fn id() -> Self::Identifier {
+            id_migracao(1, 2)
+        }
+
+        fn step(
+            cursor: Option<Self::Cursor>,
+            meter: &mut WeightMeter,
+        ) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+            let Some(cursor) = iniciar::<T, _>(cursor, 1, CursorFases::Contatos(None)) else {
+                return Ok(None);
+            };
+            em_passos(cursor, T::DbWeight::get().reads_writes(2, 1), meter, |cursor| match cursor {
+                CursorFases::Contatos(ultimo) => {
+                    let proximo = match ultimo {
+                        Some((quem, id)) => Contatos::<T>::iter_keys_from(Contatos::<T>::hashed_key_for(&quem, id)).next(),
+                        None => Contatos::<T>::iter_keys().next(),
+                    };
+                    Some(match proximo {
+                        Some((quem, id)) => {
+                            TotalContatos::<T>::mutate(&quem, |total| *total = total.saturating_add(1));
+                            CursorFases::Contatos(Some((quem, id)))
+                        }
+                        None => CursorFases::Compromissos(None),
+                    })
+                }
+                CursorFases::Compromissos(ultimo) => {
+                    let proximo = match ultimo {
+                        Some((quem, id)) => {
+                            Compromissos::<T>::iter_keys_from(Compromissos::<T>::hashed_key_for(&quem, id)).next()
+                        }
+                        None => Compromissos::<T>::iter_keys().next(),
+                    };
+                    match proximo {
+                        Some((quem, id)) => {
+                            TotalCompromissos::<T>::mutate(&quem, |total| *total = total.saturating_add(1));
+                            Some(CursorFases::Compromissos(Some((quem, id))))
+                        }
+                        None => {
+                            StorageVersion::new(2).put::<Pallet<T>>();
+                            None
+                        }
+                    }
+                }
+            })
+        }
+    }
+}
+
+pub mod v3 {
+    use super::{em_passos, id_migracao, iniciar};
+    use crate::{Config, Contatos, Pallet};
+    use core::marker::PhantomData;
+    use frame_support::{
+        migrations::{MigrationId, SteppedMigration, SteppedMigrationError},
+        pallet_prelude::*,
+        weights::WeightMeter,
+    };
+
+    // Monta o índice de busca dos contatos que já existiam antes da v3.
+    // O cursor guarda o último contato indexado (`None` enquanto nenhum foi).
+    pub struct MigrarParaV3<T>(PhantomData<T>);
+
+    impl<T: Config> SteppedMigration for MigrarParaV3<T> {
+        type Cursor = Option<(T::AccountId, u32)>;
+        type Identifier = MigrationId<14>;
+
+        fn id() -> Self::Identifier {
+            id_migracao(2, 3)
+        }
+
+        fn step(
+            cursor: Option<Self::Cursor>,
+            meter: &mut WeightMeter,
+        ) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+            let Some(cursor) = iniciar::<T, _>(cursor, 2, None) else {
+                return Ok(None);
+            };
+            let peso = T::DbWeight::get().reads_writes(1, Pallet::<T>::max_prefixos_contato());
+            em_passos(cursor, peso, meter, |ultimo| {
+                let proximo = match ultimo {
+                    Some((quem, id)) => Contatos::<T>::iter_from(Contatos::<T>::hashed_key_for(&quem, id)).next(),
+                    None => Contatos::<T>::iter().next(),
+                };
+                match proximo {
+                    Some((quem, id, contato)) => {
+                        Pallet::<T>::indexar_contato(&quem, &contato);
+                        Some(Some((quem, id)))
+                    }
+                    None => {
+                        StorageVersion::new(3).put::<Pallet<T>>();
+                        None
+                    }
+                }
+            })
+        }
+    }
+}
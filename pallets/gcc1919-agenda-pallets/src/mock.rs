@@ -25,13 +25,23 @@ mod runtime {
 
     #[runtime::pallet_index(1)]
     pub type CustomPallet = GCC1919AgendaPallet::Pallet<Test>;
+
+    #[runtime::pallet_index(2)]
+    pub type Balances = pallet_balances::Pallet<Test>;
 }
 
 // System pallet configuration
 #[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
 impl frame_system::Config for Test {
     type Block = Block;
+    type AccountData = pallet_balances::AccountData<u64>;
+    // Pesos de banco diferentes de zero para exercitar os limites da poda e das migrações
+    type DbWeight = frame_support::weights::constants::RocksDbWeight;
+}
 
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+    type AccountStore = System;
 }
 
 // Definindo os parâmetros para os valores máximos de comprimento de cada campo
@@ -41,6 +51,42 @@ parameter_types! {
     pub const MaxEmailLength: u32 = 100;
     pub const MaxTituloLength: u32 = 100;
     pub const MaxHoraLength: u32 = 5;
+    pub const MaxCapacidade: u32 = 3;
+    pub const MaxMotivoLength: u32 = 50;
+    pub const MinDuracao: u32 = 1;
+    pub const MaxDuracao: u32 = 3 * 24 * 60;
+    pub const MaxEnderecoLength: u32 = 100;
+    pub const MaxLinkLength: u32 = 100;
+    pub const MaxContatosPorCompromisso: u32 = 2;
+    pub const MaxItensChecklist: u32 = 3;
+    pub const MaxItemLength: u32 = 50;
+    pub const MaxAnexos: u32 = 2;
+    pub const MaxMimeLength: u32 = 50;
+    pub const MaxLocalizadorLength: u32 = 100;
+    pub const MaxDadosAnexo: u32 = 64;
+    pub const MaxNotasLength: u32 = 200;
+    pub const MaxLembretes: u32 = 3;
+    pub const MaxHistoricoReagendamentos: u32 = 2;
+    pub const MaxPodaPorBloco: u32 = 2;
+    pub const MaxContatosPorConta: u32 = 10;
+    pub const MaxCompromissosPorConta: u32 = 10;
+    pub static ReutilizarIds: bool = false;
+    pub static MaxEscritasPorJanela: u32 = 100;
+    pub const JanelaEscritas: u64 = 10;
+    pub static Verbosidade: GCC1919AgendaPallet::Verbosidade = GCC1919AgendaPallet::Verbosidade::Minima;
+    pub const MaxPrefixoBusca: u32 = 4;
+    pub const MaxLote: u32 = 5;
+    pub const MaxCompromissosPorContato: u32 = 3;
+    pub const MaxModelosPorConta: u32 = 2;
+    pub static Agora: u64 = 0;
+}
+
+// Relógio controlado pelos testes, em segundos desde a época Unix
+pub struct TempoMock;
+impl frame_support::traits::UnixTime for TempoMock {
+    fn now() -> core::time::Duration {
+        core::time::Duration::from_secs(Agora::get())
+    }
 }
 
 impl GCC1919AgendaPallet::Config for Test {
@@ -50,12 +96,47 @@ impl GCC1919AgendaPallet::Config for Test {
     type MaxEmailLength = MaxEmailLength;
     type MaxTituloLength = MaxTituloLength;
     type MaxHoraLength = MaxHoraLength;
+    type Tempo = TempoMock;
+    type MaxCapacidade = MaxCapacidade;
+    type RuntimeHoldReason = RuntimeHoldReason;
+    type Moeda = Balances;
+    type MaxMotivoLength = MaxMotivoLength;
+    type MinDuracao = MinDuracao;
+    type MaxDuracao = MaxDuracao;
+    type MaxEnderecoLength = MaxEnderecoLength;
+    type MaxLinkLength = MaxLinkLength;
+    type MaxContatosPorCompromisso = MaxContatosPorCompromisso;
+    type MaxItensChecklist = MaxItensChecklist;
+    type MaxItemLength = MaxItemLength;
+    type MaxAnexos = MaxAnexos;
+    type MaxMimeLength = MaxMimeLength;
+    type MaxLocalizadorLength = MaxLocalizadorLength;
+    type MaxDadosAnexo = MaxDadosAnexo;
+    type MaxNotasLength = MaxNotasLength;
+    type MaxLembretes = MaxLembretes;
+    type MaxHistoricoReagendamentos = MaxHistoricoReagendamentos;
+    type MaxPodaPorBloco = MaxPodaPorBloco;
+    type MaxContatosPorConta = MaxContatosPorConta;
+    type MaxCompromissosPorConta = MaxCompromissosPorConta;
+    type ReutilizarIds = ReutilizarIds;
+    type MaxEscritasPorJanela = MaxEscritasPorJanela;
+    type JanelaEscritas = JanelaEscritas;
+    type Verbosidade = Verbosidade;
+    type MaxPrefixoBusca = MaxPrefixoBusca;
+    type MaxLote = MaxLote;
+    type MaxCompromissosPorContato = MaxCompromissosPorContato;
+    type MaxModelosPorConta = MaxModelosPorConta;
 }
 
 // Test externalities initialization
 pub fn new_test_ext() -> sp_io::TestExternalities {
-    frame_system::GenesisConfig::<Test>::default()
+    let mut storage = frame_system::GenesisConfig::<Test>::default()
         .build_storage()
-        .unwrap()
-        .into()
+        .unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        balances: (1..=5).map(|conta| (conta, 1_000)).collect(),
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+    storage.into()
 }
@@ -0,0 +1,50 @@
+use crate::{
+    CursorLista, Disponibilidade, FiltroCompromissos, FiltroContatos, OrdemCompromissos, OrdemContatos, Pagina, Status,
+};
+use codec::Codec;
+use scale_info::prelude::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    pub trait AgendaApi<AccountId, Contato, Compromisso, Tarefa>
+    where
+        AccountId: Codec,
+        Contato: Codec,
+        Compromisso: Codec,
+        Tarefa: Codec,
+    {
+        // Intervalos ocupados (sem títulos) das contas públicas e janelas livres entre `inicio` e `fim`
+        fn disponibilidade(contas: Vec<AccountId>, inicio: u64, fim: u64, duracao: u32) -> Disponibilidade<AccountId>;
+
+        fn compromissos_por_status(conta: AccountId, status: Status) -> Vec<Compromisso>;
+
+        fn compromissos_com_contato(conta: AccountId, contato: u32) -> Vec<Compromisso>;
+
+        // Tarefas não concluídas cujo prazo já passou
+        fn tarefas_atrasadas(conta: AccountId) -> Vec<Tarefa>;
+
+        // Busca por prefixo de nome, e-mail ou telefone, sem diferenciar maiúsculas nem acentos
+        fn buscar_contatos(conta: AccountId, consulta: Vec<u8>, apos: Option<u32>, limite: u32) -> Pagina<Contato>;
+
+        // Ids de todos os contatos da conta, em ordem; usado pela busca por trecho do nó
+        fn ids_contatos(conta: AccountId) -> Vec<u32>;
+
+        fn contatos_por_ids(conta: AccountId, ids: Vec<u32>) -> Vec<Contato>;
+
+        // Listagens ordenadas; `apos` é o `proximo` da página anterior e deve vir com a mesma ordem
+        fn listar_contatos(
+            conta: AccountId,
+            filtro: FiltroContatos,
+            ordem: OrdemContatos,
+            apos: Option<CursorLista>,
+            limite: u32,
+        ) -> Pagina<Contato, CursorLista>;
+
+        fn listar_compromissos(
+            conta: AccountId,
+            filtro: FiltroCompromissos,
+            ordem: OrdemCompromissos,
+            apos: Option<CursorLista>,
+            limite: u32,
+        ) -> Pagina<Compromisso, CursorLista>;
+    }
+}
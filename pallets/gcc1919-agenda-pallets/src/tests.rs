@@ -1,5 +1,5 @@
 use frame_support::{assert_noop, assert_ok};
-use crate::mock::{new_test_ext, CustomPallet, RuntimeOrigin, Test};
+use crate::mock::{new_test_ext, Agora, Balances, CustomPallet, RuntimeOrigin, System, Test, Verbosidade};
 
 
 #[test]
@@ -284,4 +284,1987 @@ fn test_deletar_compromisso() {
         assert_ok!(CustomPallet::deletar_compromisso(RuntimeOrigin::signed(1), 0));
         assert!(CustomPallet::compromissos(1, 0).is_none());
     });
-}
\ No newline at end of file
+}
+
+fn publicar_horario_segunda(profissional: u64, capacidade: u32) {
+    publicar_horario_com_deposito(profissional, capacidade, 0);
+}
+
+fn publicar_horario_com_deposito(profissional: u64, capacidade: u32, deposito: u64) {
+    // 10/02/2025 é uma segunda-feira
+    assert_ok!(CustomPallet::publicar_horario(
+        RuntimeOrigin::signed(profissional),
+        String::from("Consulta"),
+        1,
+        String::from("14:00"),
+        30,
+        capacidade,
+        60,
+        deposito,
+    ));
+}
+
+#[test]
+fn test_reservar_horario() {
+    new_test_ext().execute_with(|| {
+        publicar_horario_segunda(2, 2);
+
+        assert_ok!(CustomPallet::reservar_horario(
+            RuntimeOrigin::signed(1),
+            2,
+            0,
+            String::from("10/02/2025"),
+        ));
+
+        let data = CustomPallet::convert_to_timestamp(b"10/02/2025".to_vec()).unwrap();
+        let reservas = CustomPallet::reservas((2, 0, data));
+        assert_eq!(reservas.len(), 1);
+        assert_eq!(reservas[0].cliente, 1);
+
+        // O compromisso é criado na agenda do cliente e na do profissional
+        let do_cliente = CustomPallet::compromissos(1, reservas[0].compromisso_cliente).unwrap();
+        let do_profissional = CustomPallet::compromissos(2, reservas[0].compromisso_profissional).unwrap();
+        assert_eq!(do_cliente.titulo, b"Consulta".to_vec());
+        assert_eq!(do_cliente.data, data);
+        assert_eq!(do_cliente, do_profissional);
+        assert_eq!(CustomPallet::reserva_do_compromisso(1, 0), Some((2, 0, data)));
+
+        // Compromissos reservados só saem da agenda pelo cancelamento da reserva
+        assert_noop!(
+            CustomPallet::deletar_compromisso(RuntimeOrigin::signed(1), 0),
+            crate::Error::<Test>::CompromissoReservado
+        );
+    });
+}
+
+#[test]
+fn test_reservar_horario_sem_dupla_reserva() {
+    new_test_ext().execute_with(|| {
+        publicar_horario_segunda(2, 1);
+        let data = String::from("10/02/2025");
+
+        assert_noop!(
+            CustomPallet::reservar_horario(RuntimeOrigin::signed(1), 2, 0, String::from("11/02/2025")),
+            crate::Error::<Test>::DiaSemanaIncompativel
+        );
+        assert_noop!(
+            CustomPallet::reservar_horario(RuntimeOrigin::signed(2), 2, 0, data.clone()),
+            crate::Error::<Test>::ReservaPropria
+        );
+
+        assert_ok!(CustomPallet::reservar_horario(RuntimeOrigin::signed(1), 2, 0, data.clone()));
+        assert_noop!(
+            CustomPallet::reservar_horario(RuntimeOrigin::signed(1), 2, 0, data.clone()),
+            crate::Error::<Test>::ReservaDuplicada
+        );
+        assert_noop!(
+            CustomPallet::reservar_horario(RuntimeOrigin::signed(3), 2, 0, data.clone()),
+            crate::Error::<Test>::HorarioLotado
+        );
+
+        // O cliente 4 já tem um compromisso que se sobrepõe ao horário
+        assert_ok!(CustomPallet::criar_compromisso(
+            RuntimeOrigin::signed(4),
+            String::from("Dentista"),
+            data.clone(),
+            String::from("14:15"),
+            crate::Prioridade::Alta,
+            30,
+        ));
+        publicar_horario_segunda(5, 1);
+        assert_noop!(
+            CustomPallet::reservar_horario(RuntimeOrigin::signed(4), 5, 0, data),
+            crate::Error::<Test>::ConflitoDeHorario
+        );
+    });
+}
+
+#[test]
+fn test_reservar_horario_com_profissional_ocupado() {
+    new_test_ext().execute_with(|| {
+        publicar_horario_segunda(2, 2);
+        let data = String::from("10/02/2025");
+
+        // Vagas do mesmo horário não conflitam entre si na agenda do profissional
+        assert_ok!(CustomPallet::reservar_horario(RuntimeOrigin::signed(1), 2, 0, data.clone()));
+        assert_ok!(CustomPallet::reservar_horario(RuntimeOrigin::signed(3), 2, 0, data.clone()));
+        assert_ok!(CustomPallet::cancelar_reserva(RuntimeOrigin::signed(3), 2, 0, data.clone(), 3));
+
+        // Um compromisso próprio do profissional às 14:15 bloqueia novas reservas
+        assert_ok!(CustomPallet::criar_compromisso(
+            RuntimeOrigin::signed(2),
+            String::from("Almoço"),
+            data.clone(),
+            String::from("14:15"),
+            crate::Prioridade::Media,
+            30,
+        ));
+        assert_noop!(
+            CustomPallet::reservar_horario(RuntimeOrigin::signed(3), 2, 0, data.clone()),
+            crate::Error::<Test>::ProfissionalOcupado
+        );
+
+        // Sem o compromisso a vaga volta a ficar disponível
+        assert_ok!(CustomPallet::deletar_compromisso(RuntimeOrigin::signed(2), 2));
+        assert_ok!(CustomPallet::reservar_horario(RuntimeOrigin::signed(3), 2, 0, data.clone()));
+
+        // Reservas de outro horário do profissional no mesmo intervalo também contam
+        assert_ok!(CustomPallet::publicar_horario(
+            RuntimeOrigin::signed(2),
+            String::from("Retorno"),
+            1,
+            String::from("14:20"),
+            30,
+            1,
+            60,
+            0,
+        ));
+        assert_noop!(
+            CustomPallet::reservar_horario(RuntimeOrigin::signed(4), 2, 1, data),
+            crate::Error::<Test>::ProfissionalOcupado
+        );
+    });
+}
+
+#[test]
+fn test_cancelar_reserva_respeita_prazo() {
+    new_test_ext().execute_with(|| {
+        publicar_horario_segunda(2, 2);
+        let data = String::from("10/02/2025");
+        assert_ok!(CustomPallet::reservar_horario(RuntimeOrigin::signed(1), 2, 0, data.clone()));
+        assert_ok!(CustomPallet::reservar_horario(RuntimeOrigin::signed(3), 2, 0, data.clone()));
+
+        // 30 minutos antes do início, depois do prazo de 60 minutos
+        let inicio = CustomPallet::convert_to_timestamp(data.clone().into_bytes()).unwrap() + 14 * 3600;
+        Agora::set(inicio - 30 * 60);
+        assert_noop!(
+            CustomPallet::cancelar_reserva(RuntimeOrigin::signed(1), 2, 0, data.clone(), 1),
+            crate::Error::<Test>::PrazoCancelamentoExpirado
+        );
+        assert_noop!(
+            CustomPallet::cancelar_reserva(RuntimeOrigin::signed(4), 2, 0, data.clone(), 1),
+            crate::Error::<Test>::SemPermissao
+        );
+
+        // O profissional pode cancelar a qualquer momento
+        assert_ok!(CustomPallet::cancelar_reserva(RuntimeOrigin::signed(2), 2, 0, data.clone(), 1));
+        assert!(CustomPallet::compromissos(1, 0).is_none());
+        assert!(CustomPallet::compromissos(2, 0).is_none());
+        assert!(CustomPallet::reserva_do_compromisso(1, 0).is_none());
+
+        Agora::set(inicio - 2 * 3600);
+        assert_ok!(CustomPallet::cancelar_reserva(RuntimeOrigin::signed(3), 2, 0, data.clone(), 3));
+        let data = CustomPallet::convert_to_timestamp(data.into_bytes()).unwrap();
+        assert!(CustomPallet::reservas((2, 0, data)).is_empty());
+    });
+}
+
+#[test]
+fn test_deposito_da_reserva() {
+    new_test_ext().execute_with(|| {
+        publicar_horario_com_deposito(2, 3, 100);
+        let data = String::from("10/02/2025");
+        let inicio = CustomPallet::convert_to_timestamp(data.clone().into_bytes()).unwrap() + 14 * 3600;
+
+        assert_ok!(CustomPallet::reservar_horario(RuntimeOrigin::signed(1), 2, 0, data.clone()));
+        assert_ok!(CustomPallet::reservar_horario(RuntimeOrigin::signed(3), 2, 0, data.clone()));
+        assert_ok!(CustomPallet::reservar_horario(RuntimeOrigin::signed(4), 2, 0, data.clone()));
+        assert_eq!(Balances::reserved_balance(1), 100);
+        assert_eq!(Balances::free_balance(1), 900);
+
+        // Cancelamento dentro do prazo devolve o depósito
+        assert_ok!(CustomPallet::cancelar_reserva(RuntimeOrigin::signed(4), 2, 0, data.clone(), 4));
+        assert_eq!(Balances::reserved_balance(4), 0);
+        assert_eq!(Balances::free_balance(4), 1_000);
+
+        // Presença e falta só podem ser registradas depois do início
+        assert_noop!(
+            CustomPallet::confirmar_presenca(RuntimeOrigin::signed(2), 0, data.clone(), 1),
+            crate::Error::<Test>::ReservaNaoIniciada
+        );
+        Agora::set(inicio + 60);
+
+        assert_ok!(CustomPallet::confirmar_presenca(RuntimeOrigin::signed(2), 0, data.clone(), 1));
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 1_000);
+        assert!(CustomPallet::reserva_do_compromisso(1, 0).is_none());
+        assert!(CustomPallet::compromissos(1, 0).is_some());
+
+        // Falta: o depósito vai para o profissional e os compromissos ficam cancelados
+        assert_ok!(CustomPallet::registrar_falta(RuntimeOrigin::signed(2), 0, data.clone(), 3));
+        assert_eq!(Balances::reserved_balance(3), 0);
+        assert_eq!(Balances::free_balance(3), 900);
+        assert_eq!(Balances::free_balance(2), 1_100);
+        let do_cliente = CustomPallet::compromissos(3, 0).unwrap();
+        assert_eq!(do_cliente.status, crate::Status::Cancelado);
+        assert_eq!(do_cliente.motivo_cancelamento, Some(b"Falta".to_vec().try_into().unwrap()));
+        assert_eq!(CustomPallet::compromissos(2, 1).unwrap().status, crate::Status::Cancelado);
+        assert!(!CustomPallet::tem_conflito(&3, inicio, inicio + 1_800, None));
+        assert_noop!(
+            CustomPallet::registrar_falta(RuntimeOrigin::signed(2), 0, data, 3),
+            crate::Error::<Test>::ReservaNaoEncontrada
+        );
+
+        assert_ok!(CustomPallet::do_try_state());
+    });
+}
+
+#[test]
+fn test_reserva_sem_saldo_para_deposito() {
+    new_test_ext().execute_with(|| {
+        publicar_horario_com_deposito(2, 1, 5_000);
+
+        assert_noop!(
+            CustomPallet::reservar_horario(RuntimeOrigin::signed(1), 2, 0, String::from("10/02/2025")),
+            crate::Error::<Test>::SaldoInsuficiente
+        );
+    });
+}
+
+#[test]
+fn test_disponibilidade_entre_contas() {
+    new_test_ext().execute_with(|| {
+        let data = String::from("10/02/2025");
+        let dia = CustomPallet::convert_to_timestamp(data.clone().into_bytes()).unwrap();
+        let criar = |conta: u64, hora: &str, duracao: u32| {
+            assert_ok!(CustomPallet::criar_compromisso(
+                RuntimeOrigin::signed(conta),
+                String::from("Reunião"),
+                data.clone(),
+                String::from(hora),
+                crate::Prioridade::Media,
+                duracao,
+            ));
+        };
+        criar(1, "09:00", 60);
+        criar(2, "09:30", 60);
+        criar(2, "13:00", 30);
+        criar(3, "11:00", 60);
+        assert_ok!(CustomPallet::definir_privacidade(RuntimeOrigin::signed(3), crate::Privacidade::Privada));
+
+        let hora = |h: u64| dia + h * 3600;
+        let resposta = CustomPallet::disponibilidade(vec![1, 2, 3], hora(8), hora(14), 120);
+
+        assert_eq!(
+            resposta.ocupado,
+            vec![
+                crate::Intervalo { inicio: hora(9), fim: hora(10) + 1800 },
+                crate::Intervalo { inicio: hora(13), fim: hora(13) + 1800 },
+            ]
+        );
+        assert_eq!(
+            resposta.livre,
+            vec![
+                crate::Intervalo { inicio: hora(8), fim: hora(9) },
+                crate::Intervalo { inicio: hora(10) + 1800, fim: hora(13) },
+                crate::Intervalo { inicio: hora(13) + 1800, fim: hora(14) },
+            ]
+        );
+        // Só a janela das 10:30 às 13:00 comporta duas horas
+        assert_eq!(
+            resposta.sugestoes,
+            vec![crate::Intervalo { inicio: hora(10) + 1800, fim: hora(12) + 1800 }]
+        );
+        assert_eq!(resposta.privadas, vec![3]);
+    });
+}
+
+#[test]
+fn test_ciclo_de_status_do_compromisso() {
+    new_test_ext().execute_with(|| {
+        for hora in ["09:00", "11:00"] {
+            assert_ok!(CustomPallet::criar_compromisso(
+                RuntimeOrigin::signed(1),
+                String::from("Reunião"),
+                String::from("10/02/2025"),
+                String::from(hora),
+                crate::Prioridade::Media,
+                30,
+            ));
+        }
+        assert_eq!(CustomPallet::compromissos(1, 0).unwrap().status, crate::Status::Agendado);
+
+        assert_ok!(CustomPallet::confirmar(RuntimeOrigin::signed(1), 0));
+        assert_noop!(
+            CustomPallet::confirmar(RuntimeOrigin::signed(1), 0),
+            crate::Error::<Test>::TransicaoInvalida
+        );
+        assert_ok!(CustomPallet::concluir(RuntimeOrigin::signed(1), 0));
+        assert_noop!(
+            CustomPallet::cancelar(RuntimeOrigin::signed(1), 0, None),
+            crate::Error::<Test>::TransicaoInvalida
+        );
+
+        assert_ok!(CustomPallet::cancelar(RuntimeOrigin::signed(1), 1, Some(String::from("Chuva"))));
+        let cancelado = CustomPallet::compromissos(1, 1).unwrap();
+        assert_eq!(cancelado.status, crate::Status::Cancelado);
+        assert_eq!(cancelado.motivo_cancelamento.unwrap(), b"Chuva".to_vec());
+        assert_noop!(
+            CustomPallet::concluir(RuntimeOrigin::signed(1), 1),
+            crate::Error::<Test>::TransicaoInvalida
+        );
+
+        let concluidos = CustomPallet::compromissos_por_status(&1, crate::Status::Concluido);
+        assert_eq!(concluidos.len(), 1);
+        assert_eq!(concluidos[0].id, 0);
+        assert!(CustomPallet::compromissos_por_status(&1, crate::Status::Agendado).is_empty());
+    });
+}
+
+#[test]
+fn test_compromisso_dia_inteiro_em_varios_dias() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(CustomPallet::criar_compromisso_dia_inteiro(
+            RuntimeOrigin::signed(1),
+            String::from("Férias"),
+            String::from("10/02/2025"),
+            Some(String::from("14/02/2025")),
+            crate::Prioridade::Baixa,
+        ));
+        assert_noop!(
+            CustomPallet::criar_compromisso_dia_inteiro(
+                RuntimeOrigin::signed(1),
+                String::from("Férias"),
+                String::from("14/02/2025"),
+                Some(String::from("10/02/2025")),
+                crate::Prioridade::Baixa,
+            ),
+            crate::Error::<Test>::DataFimInvalida
+        );
+
+        let ferias = CustomPallet::compromissos(1, 0).unwrap();
+        let inicio = CustomPallet::convert_to_timestamp(b"10/02/2025".to_vec()).unwrap();
+        assert!(ferias.dia_inteiro);
+        assert_eq!(ferias.data_fim, inicio + 4 * 86400);
+        assert_eq!(CustomPallet::intervalo_compromisso(&ferias), (inicio, inicio + 5 * 86400));
+        assert!(CustomPallet::tem_conflito(&1, inicio + 3 * 86400 + 3600, inicio + 3 * 86400 + 7200, None));
+    });
+}
+
+#[test]
+fn test_limites_de_duracao_configuraveis() {
+    new_test_ext().execute_with(|| {
+        // Acima do antigo limite de 600 minutos, dentro do limite do mock
+        assert_ok!(CustomPallet::criar_compromisso(
+            RuntimeOrigin::signed(1),
+            String::from("Congresso"),
+            String::from("10/02/2025"),
+            String::from("20:00"),
+            crate::Prioridade::Alta,
+            24 * 60,
+        ));
+        let congresso = CustomPallet::compromissos(1, 0).unwrap();
+        let inicio = CustomPallet::convert_to_timestamp(b"10/02/2025".to_vec()).unwrap();
+        assert!(!congresso.dia_inteiro);
+        assert_eq!(congresso.data_fim, inicio + 86400);
+
+        assert_noop!(
+            CustomPallet::criar_compromisso(
+                RuntimeOrigin::signed(1),
+                String::from("Congresso"),
+                String::from("10/02/2025"),
+                String::from("20:00"),
+                crate::Prioridade::Alta,
+                crate::mock::MaxDuracao::get() + 1,
+            ),
+            crate::Error::<Test>::DuracaoInvalida
+        );
+    });
+}
+
+#[test]
+fn test_definir_local_e_link() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(CustomPallet::criar_compromisso(
+            RuntimeOrigin::signed(1),
+            String::from("Consulta"),
+            String::from("10/02/2025"),
+            String::from("14:00"),
+            crate::Prioridade::Alta,
+            30,
+        ));
+
+        let local = crate::Local::Coordenadas { latitude: -23_550_520, longitude: -46_633_308 };
+        assert_ok!(CustomPallet::definir_local(
+            RuntimeOrigin::signed(1),
+            0,
+            Some(local.clone()),
+            Some(String::from("https://meet.example.com/abc-123")),
+        ));
+        let compromisso = CustomPallet::compromissos(1, 0).unwrap();
+        assert_eq!(compromisso.local, Some(local));
+        assert_eq!(compromisso.link.unwrap(), b"https://meet.example.com/abc-123".to_vec());
+
+        assert_noop!(
+            CustomPallet::definir_local(RuntimeOrigin::signed(1), 0, None, Some(String::from("ftp://arquivos"))),
+            crate::Error::<Test>::LinkInvalido
+        );
+        assert_noop!(
+            CustomPallet::definir_local(
+                RuntimeOrigin::signed(1),
+                0,
+                Some(crate::Local::Coordenadas { latitude: 91_000_000, longitude: 0 }),
+                None,
+            ),
+            crate::Error::<Test>::CoordenadasInvalidas
+        );
+
+        // Limpa local e link
+        assert_ok!(CustomPallet::definir_local(RuntimeOrigin::signed(1), 0, None, None));
+        let compromisso = CustomPallet::compromissos(1, 0).unwrap();
+        assert!(compromisso.local.is_none() && compromisso.link.is_none());
+    });
+}
+
+#[test]
+fn test_migracao_v1_do_layout_antigo() {
+    use crate::migrations::v1::{CompromissoV0, MigrarParaV1};
+    use frame_support::{traits::{GetStorageVersion, StorageVersion}, weights::Weight};
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<CustomPallet>();
+        let data = CustomPallet::convert_to_timestamp(b"10/02/2025".to_vec()).unwrap();
+        let antigo = CompromissoV0::<Test> {
+            id: 0,
+            titulo: b"Dentista".to_vec().try_into().unwrap(),
+            data,
+            hora: b"23:30".to_vec().try_into().unwrap(),
+            prioridade: crate::Prioridade::Alta,
+            duracao: 60,
+        };
+        frame_support::storage::unhashed::put(&crate::Compromissos::<Test>::hashed_key_for(1, 0), &antigo);
+
+        migrar_ate_o_fim::<MigrarParaV1<Test>>(Weight::MAX);
+
+        let migrado = CustomPallet::compromissos(1, 0).unwrap();
+        assert_eq!(migrado.titulo, antigo.titulo);
+        assert_eq!(migrado.status, crate::Status::Agendado);
+        assert_eq!(migrado.data_fim, data + 86400);
+        assert!(migrado.local.is_none() && migrado.link.is_none());
+        assert!(migrado.anexos.is_empty());
+        assert!(migrado.notas.is_empty());
+        assert!(migrado.lembretes.is_empty() && migrado.contatos.is_empty());
+        assert_eq!(CustomPallet::on_chain_storage_version(), 1);
+
+        migrar_ate_o_fim::<crate::migrations::v2::MigrarParaV2<Test>>(Weight::MAX);
+        assert_eq!(CustomPallet::total_compromissos(1), 1);
+        assert_eq!(CustomPallet::on_chain_storage_version(), 2);
+    });
+}
+
+fn criar_contato_simples(conta: u64, nome: &str) {
+    assert_ok!(CustomPallet::criar_contato(
+        RuntimeOrigin::signed(conta),
+        String::from(nome),
+        String::from("123456789"),
+        String::from("contato@example.com"),
+        30,
+        String::from("01/01/1990"),
+        crate::Categoria::Colega,
+    ));
+}
+
+#[test]
+fn test_vincular_contatos_ao_compromisso() {
+    new_test_ext().execute_with(|| {
+        criar_contato_simples(1, "Ana");
+        criar_contato_simples(1, "Bruno");
+        criar_contato_simples(1, "Carla");
+        for hora in ["09:00", "11:00"] {
+            assert_ok!(CustomPallet::criar_compromisso(
+                RuntimeOrigin::signed(1),
+                String::from("Reunião"),
+                String::from("10/02/2025"),
+                String::from(hora),
+                crate::Prioridade::Media,
+                30,
+            ));
+        }
+
+        assert_noop!(
+            CustomPallet::vincular_contatos(RuntimeOrigin::signed(1), 0, vec![0, 7]),
+            crate::Error::<Test>::ContatoNaoEncontrado
+        );
+        assert_noop!(
+            CustomPallet::vincular_contatos(RuntimeOrigin::signed(1), 0, vec![0, 1, 2]),
+            crate::Error::<Test>::MuitosContatos
+        );
+        assert_ok!(CustomPallet::vincular_contatos(RuntimeOrigin::signed(1), 0, vec![1, 0, 1]));
+        assert_ok!(CustomPallet::vincular_contatos(RuntimeOrigin::signed(1), 1, vec![1]));
+        assert_eq!(CustomPallet::compromissos(1, 0).unwrap().contatos.into_inner(), vec![0, 1]);
+
+        let com_bruno: Vec<u32> = CustomPallet::compromissos_com_contato(&1, 1).iter().map(|c| c.id).collect();
+        assert_eq!(com_bruno.len(), 2);
+        assert!(com_bruno.contains(&0) && com_bruno.contains(&1));
+
+        // Apagar o contato remove as referências
+        assert_ok!(CustomPallet::deletar_contato(RuntimeOrigin::signed(1), 1));
+        assert_eq!(CustomPallet::compromissos(1, 0).unwrap().contatos.into_inner(), vec![0]);
+        assert!(CustomPallet::compromissos(1, 1).unwrap().contatos.is_empty());
+        assert!(CustomPallet::compromissos_com_contato(&1, 1).is_empty());
+
+        // Apagar o compromisso limpa o índice reverso
+        assert_ok!(CustomPallet::deletar_compromisso(RuntimeOrigin::signed(1), 0));
+        assert!(CustomPallet::compromissos_com_contato(&1, 0).is_empty());
+
+        assert_ok!(CustomPallet::do_try_state());
+    });
+}
+
+#[test]
+fn test_crud_de_tarefas() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(CustomPallet::criar_tarefa(
+            RuntimeOrigin::signed(1),
+            String::from("Relatório"),
+            Some(String::from("10/02/2025")),
+            crate::Prioridade::Alta,
+            vec![String::from("Coletar dados"), String::from("Revisar")],
+        ));
+        let tarefa = CustomPallet::tarefas(1, 0).unwrap();
+        assert_eq!(tarefa.titulo, "Relatório".as_bytes().to_vec());
+        assert_eq!(tarefa.checklist.len(), 2);
+        assert!(!tarefa.concluida);
+        assert_eq!(CustomPallet::contador_tarefas(1), 1);
+
+        assert_ok!(CustomPallet::marcar_item_checklist(RuntimeOrigin::signed(1), 0, 1, true));
+        assert!(CustomPallet::tarefas(1, 0).unwrap().checklist[1].feito);
+        assert_noop!(
+            CustomPallet::marcar_item_checklist(RuntimeOrigin::signed(1), 0, 5, true),
+            crate::Error::<Test>::ItemNaoEncontrado
+        );
+
+        assert_noop!(
+            CustomPallet::atualizar_tarefa(
+                RuntimeOrigin::signed(1),
+                0,
+                String::from("Relatório"),
+                None,
+                crate::Prioridade::Media,
+                false,
+                vec![(String::from("a"), false); 4],
+            ),
+            crate::Error::<Test>::MuitosItens
+        );
+        assert_ok!(CustomPallet::atualizar_tarefa(
+            RuntimeOrigin::signed(1),
+            0,
+            String::from("Relatório"),
+            None,
+            crate::Prioridade::Media,
+            true,
+            vec![],
+        ));
+        let tarefa = CustomPallet::tarefas(1, 0).unwrap();
+        assert!(tarefa.concluida && tarefa.prazo.is_none() && tarefa.checklist.is_empty());
+
+        assert_ok!(CustomPallet::deletar_tarefa(RuntimeOrigin::signed(1), 0));
+        assert!(CustomPallet::tarefas(1, 0).is_none());
+        assert_noop!(
+            CustomPallet::deletar_tarefa(RuntimeOrigin::signed(1), 0),
+            crate::Error::<Test>::TarefaNaoEncontrada
+        );
+    });
+}
+
+#[test]
+fn test_tarefas_atrasadas() {
+    new_test_ext().execute_with(|| {
+        for (titulo, prazo) in [("Atrasada", Some("10/02/2025")), ("Futura", Some("20/02/2025")), ("Sem prazo", None)] {
+            assert_ok!(CustomPallet::criar_tarefa(
+                RuntimeOrigin::signed(1),
+                String::from(titulo),
+                prazo.map(String::from),
+                crate::Prioridade::Media,
+                vec![],
+            ));
+        }
+        assert_ok!(CustomPallet::criar_tarefa(
+            RuntimeOrigin::signed(1),
+            String::from("Feita"),
+            Some(String::from("01/02/2025")),
+            crate::Prioridade::Baixa,
+            vec![],
+        ));
+        assert_ok!(CustomPallet::atualizar_tarefa(
+            RuntimeOrigin::signed(1),
+            3,
+            String::from("Feita"),
+            Some(String::from("01/02/2025")),
+            crate::Prioridade::Baixa,
+            true,
+            vec![],
+        ));
+
+        // Ainda no dia do prazo a tarefa não está atrasada
+        let prazo = CustomPallet::convert_to_timestamp(b"10/02/2025".to_vec()).unwrap();
+        Agora::set(prazo + 3600);
+        assert!(CustomPallet::tarefas_atrasadas(&1).is_empty());
+
+        Agora::set(prazo + 86400);
+        let atrasadas = CustomPallet::tarefas_atrasadas(&1);
+        assert_eq!(atrasadas.len(), 1);
+        assert_eq!(atrasadas[0].titulo, b"Atrasada".to_vec());
+    });
+}
+
+#[test]
+fn test_anexos_em_contatos_e_compromissos() {
+    new_test_ext().execute_with(|| {
+        criar_contato_simples(1, "Ana");
+        assert_ok!(CustomPallet::criar_compromisso(
+            RuntimeOrigin::signed(1),
+            String::from("Reunião"),
+            String::from("10/02/2025"),
+            String::from("09:00"),
+            crate::Prioridade::Media,
+            30,
+        ));
+
+        let dados = b"%PDF-1.7 pauta da reuniao".to_vec();
+        let hash = sp_io::hashing::blake2_256(&dados);
+        let alvo = crate::AlvoAnexo::Compromisso(0);
+
+        assert_noop!(
+            CustomPallet::anexar(
+                RuntimeOrigin::signed(1),
+                alvo,
+                hash,
+                dados.len() as u64 + 1,
+                String::from("application/pdf"),
+                None,
+                Some(dados.clone().try_into().unwrap()),
+            ),
+            crate::Error::<Test>::ConteudoDivergente
+        );
+        assert_noop!(
+            CustomPallet::anexar(RuntimeOrigin::signed(1), alvo, hash, 1, String::from("pdf"), None, None),
+            crate::Error::<Test>::MimeInvalido
+        );
+
+        assert_ok!(CustomPallet::anexar(
+            RuntimeOrigin::signed(1),
+            alvo,
+            hash,
+            dados.len() as u64,
+            String::from("application/pdf"),
+            Some(String::from("ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi")),
+            Some(dados.clone().try_into().unwrap()),
+        ));
+        assert_noop!(
+            CustomPallet::anexar(RuntimeOrigin::signed(1), alvo, hash, 1, String::from("application/pdf"), None, None),
+            crate::Error::<Test>::AnexoDuplicado
+        );
+        let anexos = CustomPallet::compromissos(1, 0).unwrap().anexos;
+        assert_eq!(anexos.len(), 1);
+        assert_eq!(anexos[0].tamanho, dados.len() as u64);
+        assert_eq!(anexos[0].mime, b"application/pdf".to_vec());
+
+        // Referência sem conteúdo no contato
+        assert_ok!(CustomPallet::anexar(
+            RuntimeOrigin::signed(1),
+            crate::AlvoAnexo::Contato(0),
+            [7u8; 32],
+            2048,
+            String::from("image/jpeg"),
+            None,
+            None,
+        ));
+        assert_eq!(CustomPallet::contatos(1, 0).unwrap().anexos.len(), 1);
+        assert_noop!(
+            CustomPallet::anexar(
+                RuntimeOrigin::signed(1),
+                crate::AlvoAnexo::Contato(9),
+                [7u8; 32],
+                2048,
+                String::from("image/jpeg"),
+                None,
+                None,
+            ),
+            crate::Error::<Test>::ContatoNaoEncontrado
+        );
+
+        assert_ok!(CustomPallet::remover_anexo(RuntimeOrigin::signed(1), alvo, hash));
+        assert!(CustomPallet::compromissos(1, 0).unwrap().anexos.is_empty());
+        assert_noop!(
+            CustomPallet::remover_anexo(RuntimeOrigin::signed(1), alvo, hash),
+            crate::Error::<Test>::AnexoNaoEncontrado
+        );
+    });
+}
+
+#[test]
+fn test_conteudo_do_anexo_limitado_e_cobrado_por_byte() {
+    use codec::{Decode, Encode};
+    use frame_support::dispatch::GetDispatchInfo;
+
+    new_test_ext().execute_with(|| {
+        let peso = |bytes: usize| {
+            crate::Call::<Test>::anexar {
+                alvo: crate::AlvoAnexo::Contato(0),
+                hash: [0u8; 32],
+                tamanho: bytes as u64,
+                mime: String::from("text/plain"),
+                localizador: None,
+                dados: Some(vec![0u8; bytes].try_into().unwrap()),
+            }
+            .get_dispatch_info()
+            .weight
+        };
+        assert!(peso(64).ref_time() > peso(1).ref_time());
+        assert_eq!(peso(64) - peso(32), peso(32) - peso(0));
+
+        // Conteúdo acima de `MaxDadosAnexo` nem chega a ser decodificado
+        let chamada = |bytes: usize| {
+            let mut codificada = crate::Call::<Test>::anexar {
+                alvo: crate::AlvoAnexo::Contato(0),
+                hash: [0u8; 32],
+                tamanho: bytes as u64,
+                mime: String::from("text/plain"),
+                localizador: None,
+                dados: None,
+            }
+            .encode();
+            codificada.pop();
+            Some(vec![0u8; bytes]).encode_to(&mut codificada);
+            codificada
+        };
+        assert!(crate::Call::<Test>::decode(&mut &chamada(64)[..]).is_ok());
+        assert!(crate::Call::<Test>::decode(&mut &chamada(65)[..]).is_err());
+    });
+}
+
+#[test]
+fn test_notas_em_contatos_e_compromissos() {
+    new_test_ext().execute_with(|| {
+        criar_contato_simples(1, "Ana");
+        assert_ok!(CustomPallet::criar_compromisso(
+            RuntimeOrigin::signed(1),
+            String::from("Consulta"),
+            String::from("10/02/2025"),
+            String::from("09:00"),
+            crate::Prioridade::Media,
+            30,
+        ));
+
+        let notas = String::from("Levar exames anteriores e chegar 15 minutos antes");
+        assert_ok!(CustomPallet::atualizar_notas_compromisso(RuntimeOrigin::signed(1), 0, notas.clone()));
+        assert_ok!(CustomPallet::atualizar_notas_contato(RuntimeOrigin::signed(1), 0, String::from("Prefere WhatsApp")));
+        assert_eq!(CustomPallet::compromissos(1, 0).unwrap().notas, notas.into_bytes());
+        assert_eq!(CustomPallet::contatos(1, 0).unwrap().notas, b"Prefere WhatsApp".to_vec());
+
+        // Atualizar o contato inteiro preserva as notas
+        assert_ok!(CustomPallet::atualizar_contato(
+            RuntimeOrigin::signed(1),
+            0,
+            String::from("Ana Souza"),
+            String::from("123456789"),
+            String::from("ana@example.com"),
+            31,
+            String::from("01/01/1990"),
+            crate::Categoria::Amigo,
+        ));
+        assert_eq!(CustomPallet::contatos(1, 0).unwrap().notas, b"Prefere WhatsApp".to_vec());
+
+        assert_noop!(
+            CustomPallet::atualizar_notas_contato(RuntimeOrigin::signed(1), 0, "x".repeat(201)),
+            crate::Error::<Test>::NotasMuitoLongas
+        );
+        assert_noop!(
+            CustomPallet::atualizar_notas_compromisso(RuntimeOrigin::signed(1), 5, String::new()),
+            crate::Error::<Test>::CompromissoNaoEncontrado
+        );
+    });
+}
+
+#[test]
+fn test_limites_de_vinculos_e_modelos() {
+    use frame_support::dispatch::GetDispatchInfo;
+
+    new_test_ext().execute_with(|| {
+        criar_contato_simples(1, "Ana");
+        for dia in 10..15 {
+            assert_ok!(CustomPallet::criar_compromisso(
+                RuntimeOrigin::signed(1),
+                String::from("Reunião"),
+                format!("{dia}/02/2025"),
+                String::from("09:00"),
+                crate::Prioridade::Media,
+                30,
+            ));
+        }
+
+        // Um contato vinculado a `MaxCompromissosPorContato` compromissos não aceita mais vínculos
+        for id in 0..3 {
+            assert_ok!(CustomPallet::vincular_contatos(RuntimeOrigin::signed(1), id, vec![0]));
+        }
+        assert_noop!(
+            CustomPallet::vincular_contatos(RuntimeOrigin::signed(1), 3, vec![0]),
+            crate::Error::<Test>::LimiteAtingido
+        );
+        // Revincular um compromisso que já tem o contato não cria vínculo novo
+        assert_ok!(CustomPallet::vincular_contatos(RuntimeOrigin::signed(1), 2, vec![0]));
+
+        // O limite também vale para compromissos criados de modelos
+        assert_ok!(CustomPallet::criar_modelo(
+            RuntimeOrigin::signed(1),
+            String::from("Consulta"),
+            30,
+            crate::Prioridade::Media,
+            vec![],
+            vec![0],
+        ));
+        assert_noop!(
+            CustomPallet::criar_de_modelo(
+                RuntimeOrigin::signed(1),
+                0,
+                String::from("20/02/2025"),
+                String::from("09:00"),
+                None,
+                None,
+                None,
+            ),
+            crate::Error::<Test>::LimiteAtingido
+        );
+
+        // No máximo `MaxModelosPorConta` modelos; apagar um libera a vaga
+        assert_ok!(CustomPallet::criar_modelo(
+            RuntimeOrigin::signed(1),
+            String::from("Retorno"),
+            15,
+            crate::Prioridade::Baixa,
+            vec![],
+            vec![],
+        ));
+        let terceiro = || {
+            CustomPallet::criar_modelo(
+                RuntimeOrigin::signed(1),
+                String::from("Exame"),
+                60,
+                crate::Prioridade::Alta,
+                vec![],
+                vec![],
+            )
+        };
+        assert_noop!(terceiro(), crate::Error::<Test>::LimiteAtingido);
+        assert_ok!(CustomPallet::deletar_modelo(RuntimeOrigin::signed(1), 1));
+        assert_ok!(terceiro());
+
+        // A remoção de um contato paga a limpeza dos vínculos e dos modelos
+        let vazio = crate::Call::<Test>::deletar_contatos { ids: Default::default(), modo: crate::ModoLote::TudoOuNada }
+            .get_dispatch_info()
+            .weight;
+        let um = crate::Call::<Test>::deletar_contato { id: 0 }.get_dispatch_info().weight;
+        let db = <Test as frame_system::Config>::DbWeight::get();
+        assert!(um.all_gte(vazio + db.reads_writes(2 * 3 + 2 * 2, 2 * 3 + 2)));
+
+        assert_ok!(CustomPallet::do_try_state());
+    });
+}
+
+#[test]
+fn test_criar_compromisso_de_modelo() {
+    new_test_ext().execute_with(|| {
+        criar_contato_simples(1, "Dra. Paula");
+        assert_ok!(CustomPallet::criar_modelo(
+            RuntimeOrigin::signed(1),
+            String::from("Consulta"),
+            30,
+            crate::Prioridade::Alta,
+            vec![15, 60, 15],
+            vec![0],
+        ));
+        let modelo = CustomPallet::modelos(1, 0).unwrap();
+        assert_eq!(modelo.lembretes.to_vec(), vec![60, 15]);
+
+        // Usa os valores do modelo
+        assert_ok!(CustomPallet::criar_de_modelo(
+            RuntimeOrigin::signed(1),
+            0,
+            String::from("10/02/2025"),
+            String::from("09:00"),
+            None,
+            None,
+            None,
+        ));
+        let compromisso = CustomPallet::compromissos(1, 0).unwrap();
+        assert_eq!(compromisso.titulo.to_vec(), b"Consulta".to_vec());
+        assert_eq!(compromisso.duracao, 30);
+        assert_eq!(compromisso.prioridade, crate::Prioridade::Alta);
+        assert_eq!(compromisso.lembretes.to_vec(), vec![60, 15]);
+        assert_eq!(compromisso.contatos.to_vec(), vec![0]);
+        assert_eq!(CustomPallet::compromissos_com_contato(&1, 0).len(), 1);
+
+        // Sobrescreve título e duração
+        assert_ok!(CustomPallet::criar_de_modelo(
+            RuntimeOrigin::signed(1),
+            0,
+            String::from("11/02/2025"),
+            String::from("14:00"),
+            Some(String::from("Retorno")),
+            Some(15),
+            None,
+        ));
+        let compromisso = CustomPallet::compromissos(1, 1).unwrap();
+        assert_eq!(compromisso.titulo.to_vec(), b"Retorno".to_vec());
+        assert_eq!(compromisso.duracao, 15);
+        assert_eq!(compromisso.prioridade, crate::Prioridade::Alta);
+
+        // Participante removido deixa de fazer parte do modelo
+        assert_ok!(CustomPallet::deletar_contato(RuntimeOrigin::signed(1), 0));
+        assert!(CustomPallet::modelos(1, 0).unwrap().participantes.is_empty());
+
+        assert_noop!(
+            CustomPallet::criar_modelo(
+                RuntimeOrigin::signed(1),
+                String::from("Reunião"),
+                60,
+                crate::Prioridade::Media,
+                vec![5, 10, 30, 60],
+                vec![],
+            ),
+            crate::Error::<Test>::MuitosLembretes
+        );
+        assert_noop!(
+            CustomPallet::criar_de_modelo(
+                RuntimeOrigin::signed(2),
+                0,
+                String::from("10/02/2025"),
+                String::from("09:00"),
+                None,
+                None,
+                None,
+            ),
+            crate::Error::<Test>::ModeloNaoEncontrado
+        );
+
+        assert_ok!(CustomPallet::deletar_modelo(RuntimeOrigin::signed(1), 0));
+        assert_eq!(CustomPallet::modelos(1, 0), None);
+
+        assert_ok!(CustomPallet::do_try_state());
+    });
+}
+
+#[test]
+fn test_reagendar_compromisso() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        criar_contato_simples(1, "Ana");
+        for hora in ["09:00", "11:00"] {
+            assert_ok!(CustomPallet::criar_compromisso(
+                RuntimeOrigin::signed(1),
+                String::from("Reunião"),
+                String::from("10/02/2025"),
+                String::from(hora),
+                crate::Prioridade::Media,
+                60,
+            ));
+        }
+        assert_ok!(CustomPallet::vincular_contatos(RuntimeOrigin::signed(1), 0, vec![0]));
+        let segunda = CustomPallet::convert_to_timestamp(b"10/02/2025".to_vec()).unwrap();
+
+        // Conflita com o compromisso das 11:00
+        assert_noop!(
+            CustomPallet::reagendar(RuntimeOrigin::signed(1), 0, String::from("10/02/2025"), Some(String::from("10:30"))),
+            crate::Error::<Test>::ConflitoDeHorario
+        );
+        // Sobrepor o próprio horário antigo não é conflito
+        assert_ok!(CustomPallet::reagendar(
+            RuntimeOrigin::signed(1),
+            0,
+            String::from("10/02/2025"),
+            Some(String::from("09:30")),
+        ));
+        System::assert_last_event(
+            crate::Event::CompromissoReagendado {
+                quem: 1,
+                id: 0,
+                de: crate::Intervalo { inicio: segunda + 9 * 3600, fim: segunda + 10 * 3600 },
+                para: crate::Intervalo { inicio: segunda + 9 * 3600 + 1800, fim: segunda + 10 * 3600 + 1800 },
+                participantes: vec![0].try_into().unwrap(),
+                lembretes: Default::default(),
+            }
+            .into(),
+        );
+
+        let compromisso = CustomPallet::compromissos(1, 0).unwrap();
+        assert_eq!(compromisso.hora.to_vec(), b"09:30".to_vec());
+        assert_eq!(compromisso.duracao, 60);
+        assert_eq!(compromisso.contatos.to_vec(), vec![0]);
+
+        // O histórico guarda só os horários mais recentes
+        for hora in ["14:00", "16:00"] {
+            assert_ok!(CustomPallet::reagendar(
+                RuntimeOrigin::signed(1),
+                0,
+                String::from("11/02/2025"),
+                Some(String::from(hora)),
+            ));
+        }
+        let historico = CustomPallet::historico_reagendamentos(1, 0);
+        assert_eq!(historico.len(), 2);
+        assert_eq!(historico[0].inicio, segunda + 9 * 3600 + 1800);
+        assert_eq!(historico[1].inicio, segunda + 86400 + 14 * 3600);
+
+        assert_ok!(CustomPallet::cancelar(RuntimeOrigin::signed(1), 0, None));
+        assert_noop!(
+            CustomPallet::reagendar(RuntimeOrigin::signed(1), 0, String::from("12/02/2025"), Some(String::from("09:00"))),
+            crate::Error::<Test>::CompromissoEncerrado
+        );
+
+        assert_ok!(CustomPallet::do_try_state());
+    });
+}
+
+#[test]
+fn test_reagendar_recalcula_lembretes() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(CustomPallet::criar_modelo(
+            RuntimeOrigin::signed(1),
+            String::from("Consulta"),
+            30,
+            crate::Prioridade::Media,
+            vec![60, 15],
+            vec![],
+        ));
+        assert_ok!(CustomPallet::criar_de_modelo(
+            RuntimeOrigin::signed(1),
+            0,
+            String::from("10/02/2025"),
+            String::from("14:00"),
+            None,
+            None,
+            None,
+        ));
+        let segunda = CustomPallet::convert_to_timestamp(b"10/02/2025".to_vec()).unwrap();
+
+        // Às 09:10, trazer a consulta para as 10:00 deixa o lembrete de uma hora no passado
+        Agora::set(segunda + 9 * 3600 + 600);
+        assert_ok!(CustomPallet::reagendar(
+            RuntimeOrigin::signed(1),
+            0,
+            String::from("10/02/2025"),
+            Some(String::from("10:00")),
+        ));
+        System::assert_last_event(
+            crate::Event::CompromissoReagendado {
+                quem: 1,
+                id: 0,
+                de: crate::Intervalo { inicio: segunda + 14 * 3600, fim: segunda + 14 * 3600 + 1800 },
+                para: crate::Intervalo { inicio: segunda + 10 * 3600, fim: segunda + 10 * 3600 + 1800 },
+                participantes: Default::default(),
+                lembretes: vec![segunda + 10 * 3600 - 15 * 60].try_into().unwrap(),
+            }
+            .into(),
+        );
+
+        // Os dois voltam a valer num horário mais distante, sempre relativos ao novo início
+        assert_ok!(CustomPallet::reagendar(
+            RuntimeOrigin::signed(1),
+            0,
+            String::from("11/02/2025"),
+            Some(String::from("09:00")),
+        ));
+        let compromisso = CustomPallet::compromissos(1, 0).unwrap();
+        let inicio = segunda + 86400 + 9 * 3600;
+        assert_eq!(compromisso.lembretes.to_vec(), vec![60, 15]);
+        assert_eq!(
+            CustomPallet::lembretes_pendentes(&compromisso, Agora::get()).to_vec(),
+            vec![inicio - 3600, inicio - 15 * 60]
+        );
+    });
+}
+
+#[test]
+fn test_retencao_poda_e_arquiva_compromissos_antigos() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        for conta in [1, 2] {
+            for data in ["03/02/2025", "04/02/2025", "10/02/2025"] {
+                assert_ok!(CustomPallet::criar_compromisso(
+                    RuntimeOrigin::signed(conta),
+                    String::from("Consulta"),
+                    String::from(data),
+                    String::from("09:00"),
+                    crate::Prioridade::Media,
+                    30,
+                ));
+            }
+        }
+        // A conta 3 mantém tudo por padrão
+        assert_ok!(CustomPallet::criar_compromisso(
+            RuntimeOrigin::signed(3),
+            String::from("Consulta"),
+            String::from("03/02/2025"),
+            String::from("09:00"),
+            crate::Prioridade::Media,
+            30,
+        ));
+        assert_ok!(CustomPallet::definir_retencao(RuntimeOrigin::signed(1), crate::Retencao::Apagar { dias: 3 }));
+        assert_ok!(CustomPallet::definir_retencao(RuntimeOrigin::signed(2), crate::Retencao::Arquivar { dias: 3 }));
+        Agora::set(CustomPallet::convert_to_timestamp(b"07/02/2025".to_vec()).unwrap());
+
+        // Cada chamada examina no máximo `MaxPodaPorBloco` compromissos
+        for _ in 0..4 {
+            assert!(CustomPallet::podar_historico(frame_support::weights::Weight::MAX) != frame_support::weights::Weight::zero());
+        }
+        assert_eq!(crate::CursorPoda::<Test>::get(), None);
+
+        assert_eq!(CustomPallet::compromissos(1, 0), None);
+        assert!(CustomPallet::compromissos(1, 1).is_some());
+        assert!(CustomPallet::compromissos(1, 2).is_some());
+        assert_eq!(CustomPallet::compromissos(2, 0), None);
+        let arquivado = CustomPallet::arquivados(2, 0).unwrap();
+        assert_eq!(arquivado.titulo.to_vec(), b"Consulta".to_vec());
+        assert_eq!(arquivado.fim - arquivado.inicio, 30 * 60);
+        assert!(CustomPallet::compromissos(3, 0).is_some());
+
+        // Sem peso disponível nada é feito
+        Agora::set(CustomPallet::convert_to_timestamp(b"20/02/2025".to_vec()).unwrap());
+        assert_eq!(
+            CustomPallet::podar_historico(frame_support::weights::Weight::zero()),
+            frame_support::weights::Weight::zero()
+        );
+        assert!(CustomPallet::compromissos(1, 1).is_some());
+
+        assert_ok!(CustomPallet::do_try_state());
+    });
+}
+
+#[test]
+fn test_limite_de_itens_e_reuso_de_ids() {
+    new_test_ext().execute_with(|| {
+        for i in 0..10 {
+            criar_contato_simples(1, &format!("Contato {}", i));
+        }
+        assert_noop!(
+            CustomPallet::criar_contato(
+                RuntimeOrigin::signed(1),
+                String::from("Excedente"),
+                String::from("123456789"),
+                String::from("contato@example.com"),
+                30,
+                String::from("01/01/1990"),
+                crate::Categoria::Colega,
+            ),
+            crate::Error::<Test>::LimiteAtingido
+        );
+
+        // Sem reuso, o id apagado não volta
+        assert_ok!(CustomPallet::deletar_contato(RuntimeOrigin::signed(1), 3));
+        assert_eq!(CustomPallet::total_contatos(1), 9);
+        criar_contato_simples(1, "Novo");
+        assert!(CustomPallet::contatos(1, 10).is_some());
+
+        // Com reuso, o próximo contato ocupa o id liberado
+        crate::mock::ReutilizarIds::set(true);
+        assert_ok!(CustomPallet::deletar_contato(RuntimeOrigin::signed(1), 5));
+        criar_contato_simples(1, "Reaproveitado");
+        assert_eq!(CustomPallet::contatos(1, 5).unwrap().nome.to_vec(), b"Reaproveitado".to_vec());
+        assert_eq!(CustomPallet::contador_contatos(1), 11);
+
+        // O contador nunca dá a volta
+        crate::ContadorCompromissos::<Test>::insert(2, u32::MAX);
+        assert_noop!(
+            CustomPallet::criar_compromisso(
+                RuntimeOrigin::signed(2),
+                String::from("Consulta"),
+                String::from("10/02/2025"),
+                String::from("09:00"),
+                crate::Prioridade::Media,
+                30,
+            ),
+            crate::Error::<Test>::LimiteAtingido
+        );
+
+        assert_ok!(CustomPallet::do_try_state());
+    });
+}
+
+#[test]
+fn test_cota_de_escritas_por_janela() {
+    use crate::extensao::{VerificarCota, COTA_DE_ESCRITAS_EXCEDIDA, LIMITE_ATINGIDO};
+    use sp_runtime::{
+        traits::SignedExtension,
+        transaction_validity::{InvalidTransaction, TransactionValidityError},
+    };
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        crate::mock::MaxEscritasPorJanela::set(2);
+        let tarefa = || {
+            CustomPallet::criar_tarefa(RuntimeOrigin::signed(1), String::from("Ligar"), None, crate::Prioridade::Baixa, vec![])
+        };
+        let chamada: crate::mock::RuntimeCall = crate::Call::<Test>::criar_tarefa {
+            titulo: String::from("Ligar"),
+            prazo: None,
+            prioridade: crate::Prioridade::Baixa,
+            checklist: vec![],
+        }
+        .into();
+        let info = Default::default();
+
+        assert_ok!(tarefa());
+        assert_ok!(VerificarCota::<Test>::new().validate(&1, &chamada, &info, 0));
+        assert_ok!(tarefa());
+        assert_noop!(tarefa(), crate::Error::<Test>::CotaDeEscritasExcedida);
+        assert_eq!(
+            VerificarCota::<Test>::new().validate(&1, &chamada, &info, 0),
+            Err(TransactionValidityError::Invalid(InvalidTransaction::Custom(COTA_DE_ESCRITAS_EXCEDIDA)))
+        );
+        // Outras contas têm a própria cota
+        assert_ok!(VerificarCota::<Test>::new().validate(&2, &chamada, &info, 0));
+
+        // Uma nova janela zera a contagem
+        System::set_block_number(11);
+        assert_eq!(CustomPallet::escritas_na_janela(&1), 0);
+        assert_ok!(tarefa());
+
+        // O limite de itens vivos também é checado na validação
+        crate::TotalContatos::<Test>::insert(3, 10);
+        let chamada: crate::mock::RuntimeCall = crate::Call::<Test>::criar_contato {
+            nome: String::from("Ana"),
+            telefone: String::from("123456789"),
+            email: String::from("ana@example.com"),
+            idade: 30,
+            data_aniversario: String::from("01/01/1990"),
+            categoria: crate::Categoria::Amigo,
+        }
+        .into();
+        assert_eq!(
+            VerificarCota::<Test>::new().validate(&3, &chamada, &info, 0),
+            Err(TransactionValidityError::Invalid(InvalidTransaction::Custom(LIMITE_ATINGIDO)))
+        );
+    });
+}
+
+#[test]
+fn test_genesis_carrega_agendas_do_json() {
+    use sp_runtime::BuildStorage;
+
+    let genesis: crate::GenesisConfig<Test> = serde_json::from_value(serde_json::json!({
+        "contatos": [
+            [1, {
+                "nome": "Bob",
+                "telefone": "11999990000",
+                "email": "bob@example.com",
+                "idade": 30,
+                "dataAniversario": "15/03/1995",
+                "categoria": "Amigo"
+            }]
+        ],
+        "compromissos": [
+            [1, { "titulo": "Daily", "data": "10/02/2025", "hora": "09:00", "prioridade": "Media", "duracao": 15 }],
+            [2, { "titulo": "Dentista", "data": "11/02/2025", "hora": "14:30", "prioridade": "Alta", "duracao": 60 }]
+        ]
+    }))
+    .unwrap();
+
+    let mut storage = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+    genesis.assimilate_storage(&mut storage).unwrap();
+    sp_io::TestExternalities::from(storage).execute_with(|| {
+        let contato = CustomPallet::contatos(1, 0).unwrap();
+        assert_eq!(contato.nome.to_vec(), b"Bob".to_vec());
+        assert_eq!(contato.data_aniversario, CustomPallet::convert_to_timestamp(b"15/03/1995".to_vec()).unwrap());
+        assert_eq!(CustomPallet::compromissos(1, 0).unwrap().titulo.to_vec(), b"Daily".to_vec());
+        assert_eq!(CustomPallet::compromissos(2, 0).unwrap().prioridade, crate::Prioridade::Alta);
+        assert_eq!(CustomPallet::total_compromissos(2), 1);
+
+        assert_ok!(CustomPallet::do_try_state());
+    });
+}
+
+#[test]
+#[should_panic(expected = "compromisso inválido no genesis")]
+fn test_genesis_rejeita_dados_invalidos() {
+    use sp_runtime::BuildStorage;
+
+    crate::GenesisConfig::<Test> {
+        contatos: vec![],
+        compromissos: vec![(
+            1,
+            crate::CompromissoGenesis {
+                titulo: String::from("Reunião"),
+                data: String::from("10-02-2025"),
+                hora: String::from("09:00"),
+                prioridade: crate::Prioridade::Media,
+                duracao: 30,
+            },
+        )],
+    }
+    .build_storage()
+    .unwrap();
+}
+
+// Grava contatos e compromissos no layout anterior à v1, espalhados por três contas
+fn popular_estado_v0() {
+    use crate::migrations::v1::{CompromissoV0, ContatoV0};
+    use frame_support::traits::StorageVersion;
+
+    StorageVersion::new(0).put::<CustomPallet>();
+    let data = CustomPallet::convert_to_timestamp(b"10/02/2025".to_vec()).unwrap();
+    for conta in 1..=3u64 {
+        for id in 0..2u32 {
+            let contato = ContatoV0::<Test> {
+                id,
+                nome: b"Contato".to_vec().try_into().unwrap(),
+                telefone: b"123456789".to_vec().try_into().unwrap(),
+                email: b"contato@example.com".to_vec().try_into().unwrap(),
+                idade: 30,
+                data_aniversario: 0,
+                categoria: crate::Categoria::Amigo,
+            };
+            frame_support::storage::unhashed::put(&crate::Contatos::<Test>::hashed_key_for(conta, id), &contato);
+            let compromisso = CompromissoV0::<Test> {
+                id,
+                titulo: "Reunião".as_bytes().to_vec().try_into().unwrap(),
+                data,
+                hora: b"09:00".to_vec().try_into().unwrap(),
+                prioridade: crate::Prioridade::Media,
+                duracao: 30,
+            };
+            frame_support::storage::unhashed::put(&crate::Compromissos::<Test>::hashed_key_for(conta, id), &compromisso);
+        }
+        crate::ContadorContatos::<Test>::insert(conta, 2);
+        crate::ContadorCompromissos::<Test>::insert(conta, 2);
+    }
+}
+
+// Roda a migração em passos de no máximo `peso` até ela devolver o cursor `None` e diz quantos passos levou
+fn migrar_ate_o_fim<M: frame_support::migrations::SteppedMigration>(peso: frame_support::weights::Weight) -> u32 {
+    let mut cursor = None;
+    for passos in 1..=1000 {
+        let mut meter = frame_support::weights::WeightMeter::with_limit(peso);
+        cursor = M::step(cursor, &mut meter).unwrap();
+        if cursor.is_none() {
+            return passos;
+        }
+    }
+    panic!("a migração não terminou em 1000 passos");
+}
+
+#[test]
+fn test_migracoes_v1_e_v2_com_estado_populado() {
+    use crate::migrations::{v1::MigrarParaV1, v2::MigrarParaV2};
+    use frame_support::{traits::GetStorageVersion, weights::Weight};
+
+    new_test_ext().execute_with(|| {
+        popular_estado_v0();
+
+        // Fora de ordem a v2 não faz nada
+        assert_eq!(migrar_ate_o_fim::<MigrarParaV2<Test>>(Weight::MAX), 1);
+        assert_eq!(CustomPallet::total_contatos(1), 0);
+
+        migrar_ate_o_fim::<MigrarParaV1<Test>>(Weight::MAX);
+        migrar_ate_o_fim::<MigrarParaV2<Test>>(Weight::MAX);
+        assert_eq!(CustomPallet::on_chain_storage_version(), 2);
+        for conta in 1..=3u64 {
+            assert_eq!(CustomPallet::total_contatos(conta), 2);
+            assert_eq!(CustomPallet::total_compromissos(conta), 2);
+            assert!(CustomPallet::contatos(conta, 1).unwrap().notas.is_empty());
+            assert!(CustomPallet::contatos(conta, 1).unwrap().anexos.is_empty());
+            assert!(CustomPallet::compromissos(conta, 1).unwrap().notas.is_empty());
+        }
+
+        // Rodar de novo não conta os itens duas vezes
+        migrar_ate_o_fim::<MigrarParaV2<Test>>(Weight::MAX);
+        assert_eq!(CustomPallet::total_contatos(1), 2);
+
+        // A v3 monta o índice de busca dos contatos já existentes
+        assert!(CustomPallet::buscar_contatos(&1, b"cont".to_vec(), None, 10).itens.is_empty());
+        migrar_ate_o_fim::<crate::migrations::v3::MigrarParaV3<Test>>(Weight::MAX);
+        assert_eq!(CustomPallet::on_chain_storage_version(), 3);
+        assert_eq!(CustomPallet::buscar_contatos(&1, b"cont".to_vec(), None, 10).itens.len(), 2);
+
+        assert_ok!(CustomPallet::do_try_state());
+    });
+}
+
+#[test]
+fn test_migracoes_avancam_em_varios_blocos() {
+    use crate::migrations::{v1::MigrarParaV1, v2::MigrarParaV2, v3::MigrarParaV3, CursorFases};
+    use frame_support::{
+        migrations::{SteppedMigration, SteppedMigrationError},
+        traits::GetStorageVersion,
+        weights::{Weight, WeightMeter},
+    };
+
+    new_test_ext().execute_with(|| {
+        popular_estado_v0();
+        let db = <Test as frame_system::Config>::DbWeight::get();
+
+        // Sem peso para um item o passo é recusado sem mexer no storage
+        let mut meter = WeightMeter::with_limit(db.reads_writes(1, 0));
+        assert!(matches!(
+            MigrarParaV1::<Test>::step(None, &mut meter),
+            Err(SteppedMigrationError::InsufficientWeight { required }) if required == db.reads_writes(1, 1)
+        ));
+        assert_eq!(CustomPallet::on_chain_storage_version(), 0);
+
+        // Cada passo com peso para dois itens traduz dois contatos e para ali
+        let mut meter = WeightMeter::with_limit(db.reads_writes(2, 2));
+        let cursor = MigrarParaV1::<Test>::step(None, &mut meter).unwrap();
+        assert_eq!(meter.remaining(), Weight::zero());
+        let Some(CursorFases::Contatos(Some((quem, id)))) = cursor.clone() else {
+            panic!("cursor fora da fase de contatos: {cursor:?}");
+        };
+        assert!(CustomPallet::contatos(quem, id).is_some());
+        assert_eq!(CustomPallet::on_chain_storage_version(), 0);
+
+        // O resto continua do cursor: 4 contatos, a troca de fase, 6 compromissos e o fim
+        let mut cursor = cursor;
+        let mut passos = 1;
+        while cursor.is_some() {
+            let mut meter = WeightMeter::with_limit(db.reads_writes(2, 2));
+            cursor = MigrarParaV1::<Test>::step(cursor, &mut meter).unwrap();
+            passos += 1;
+        }
+        assert_eq!(passos, 7);
+        assert_eq!(crate::Contatos::<Test>::iter().count(), 6);
+        assert_eq!(crate::Compromissos::<Test>::iter().count(), 6);
+        assert_eq!(CustomPallet::on_chain_storage_version(), 1);
+
+        // Os totais e o índice também param no meio e terminam nos passos seguintes
+        assert_eq!(migrar_ate_o_fim::<MigrarParaV2<Test>>(db.reads_writes(4, 2)), 7);
+        assert_eq!(CustomPallet::total_contatos(2), 2);
+        assert_eq!(CustomPallet::total_compromissos(3), 2);
+        let peso_contato = db.reads_writes(1, CustomPallet::max_prefixos_contato());
+        assert_eq!(migrar_ate_o_fim::<MigrarParaV3<Test>>(peso_contato), 7);
+        assert_eq!(CustomPallet::on_chain_storage_version(), 3);
+        assert_ok!(CustomPallet::do_try_state());
+
+        // Já na versão atual nenhuma delas faz nada
+        assert_eq!(migrar_ate_o_fim::<MigrarParaV1<Test>>(Weight::MAX), 1);
+        assert_eq!(migrar_ate_o_fim::<MigrarParaV2<Test>>(Weight::MAX), 1);
+        assert_eq!(CustomPallet::total_contatos(1), 2);
+    });
+}
+
+#[test]
+fn test_try_state_detecta_inconsistencias() {
+    new_test_ext().execute_with(|| {
+        criar_contato_simples(1, "Ana");
+        assert_ok!(CustomPallet::criar_compromisso(
+            RuntimeOrigin::signed(1),
+            String::from("Consulta"),
+            String::from("10/02/2025"),
+            String::from("09:00"),
+            crate::Prioridade::Media,
+            30,
+        ));
+        assert_ok!(CustomPallet::vincular_contatos(RuntimeOrigin::signed(1), 0, vec![0]));
+        assert_ok!(CustomPallet::do_try_state());
+
+        // Índice reverso sem o vínculo correspondente
+        crate::CompromissosDoContato::<Test>::insert((1, 0, 7), ());
+        assert!(CustomPallet::do_try_state().is_err());
+        crate::CompromissosDoContato::<Test>::remove((1, 0, 7));
+
+        // Id gravado acima do contador
+        let mut compromisso = CustomPallet::compromissos(1, 0).unwrap();
+        compromisso.id = 5;
+        crate::Compromissos::<Test>::insert(1, 5, compromisso.clone());
+        assert!(CustomPallet::do_try_state().is_err());
+        crate::Compromissos::<Test>::remove(1, 5);
+
+        // Duração fora dos limites
+        compromisso.id = 0;
+        compromisso.duracao = 0;
+        crate::Compromissos::<Test>::insert(1, 0, compromisso);
+        assert!(CustomPallet::do_try_state().is_err());
+    });
+}
+
+#[test]
+fn test_eventos_com_registro_completo() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+
+        // Por padrão os eventos só identificam o registro
+        criar_contato_simples(1, "Ana");
+        System::assert_last_event(crate::Event::ContatoCriado { quem: 1, id: 0, contato: None }.into());
+
+        Verbosidade::set(crate::Verbosidade::Completa);
+        criar_contato_simples(1, "Bia");
+        let criado = CustomPallet::contatos(1, 1).unwrap();
+        System::assert_last_event(crate::Event::ContatoCriado { quem: 1, id: 1, contato: Some(criado) }.into());
+
+        // A atualização leva o registro como ficou
+        assert_ok!(CustomPallet::atualizar_notas_contato(RuntimeOrigin::signed(1), 1, String::from("Prefere e-mail")));
+        let atualizado = CustomPallet::contatos(1, 1).unwrap();
+        assert_eq!(atualizado.notas.to_vec(), b"Prefere e-mail".to_vec());
+        System::assert_last_event(
+            crate::Event::ContatoAtualizado { quem: 1, id: 1, contato: Some(atualizado.clone()) }.into(),
+        );
+
+        // A remoção leva o último estado antes de apagar
+        assert_ok!(CustomPallet::deletar_contato(RuntimeOrigin::signed(1), 1));
+        System::assert_last_event(crate::Event::ContatoDeletado { quem: 1, id: 1, contato: Some(atualizado) }.into());
+
+        assert_ok!(CustomPallet::criar_compromisso(
+            RuntimeOrigin::signed(1),
+            String::from("Consulta"),
+            String::from("10/02/2025"),
+            String::from("09:00"),
+            crate::Prioridade::Media,
+            30,
+        ));
+        let compromisso = CustomPallet::compromissos(1, 0).unwrap();
+        System::assert_last_event(
+            crate::Event::CompromissoCriado { quem: 1, id: 0, compromisso: Some(compromisso.clone()) }.into(),
+        );
+        assert_ok!(CustomPallet::deletar_compromisso(RuntimeOrigin::signed(1), 0));
+        System::assert_last_event(
+            crate::Event::CompromissoDeletado { quem: 1, id: 0, compromisso: Some(compromisso) }.into(),
+        );
+        assert_ok!(CustomPallet::do_try_state());
+    });
+}
+
+#[test]
+fn test_buscar_contatos_por_prefixo() {
+    new_test_ext().execute_with(|| {
+        let criar = |nome: &str, telefone: &str, email: &str| {
+            assert_ok!(CustomPallet::criar_contato(
+                RuntimeOrigin::signed(1),
+                String::from(nome),
+                String::from(telefone),
+                String::from(email),
+                30,
+                String::from("01/01/1990"),
+                crate::Categoria::Amigo,
+            ));
+        };
+        criar("João Álvares", "(11) 98888-7777", "joao@example.com");
+        criar("Joana Silva", "11 3333-4444", "jo.silva@example.com");
+        criar("ÁLVARO Souza", "21999990000", "alvaro@exemplo.com.br");
+        criar_contato_simples(2, "João");
+
+        let ids = |consulta: &str| -> Vec<u32> {
+            CustomPallet::buscar_contatos(&1, consulta.as_bytes().to_vec(), None, 10)
+                .itens
+                .iter()
+                .map(|contato| contato.id)
+                .collect()
+        };
+
+        // Sem diferenciar maiúsculas nem acentos, em qualquer palavra do nome
+        assert_eq!(ids("joa"), vec![0, 1]);
+        assert_eq!(ids("JOÃO"), vec![0]);
+        assert_eq!(ids("alva"), vec![0, 2]);
+        assert_eq!(ids("Álvaro"), vec![2]);
+        // Consultas maiores que o prefixo indexado são conferidas no contato
+        assert_eq!(ids("joana"), vec![1]);
+        assert_eq!(ids("joanna"), Vec::<u32>::new());
+        // Todas as palavras precisam casar
+        assert_eq!(ids("jo silva"), vec![1]);
+        // E-mail e telefone, com ou sem pontuação
+        assert_eq!(ids("jo.s"), vec![1]);
+        assert_eq!(ids("alvaro@exemplo"), vec![2]);
+        assert_eq!(ids("(11) 9888"), vec![0]);
+        assert_eq!(ids("1133"), vec![1]);
+        assert_eq!(ids("  "), Vec::<u32>::new());
+
+        // Paginação a partir do cursor
+        let pagina = CustomPallet::buscar_contatos(&1, b"a".to_vec(), None, 1);
+        assert_eq!(pagina.itens.iter().map(|contato| contato.id).collect::<Vec<_>>(), vec![0]);
+        assert_eq!(pagina.proximo, Some(0));
+        let pagina = CustomPallet::buscar_contatos(&1, b"a".to_vec(), pagina.proximo, 1);
+        assert_eq!(pagina.itens.iter().map(|contato| contato.id).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(pagina.proximo, None);
+
+        // O índice acompanha atualizações e remoções
+        assert_ok!(CustomPallet::atualizar_contato(
+            RuntimeOrigin::signed(1),
+            1,
+            String::from("Mariana Silva"),
+            String::from("11 3333-4444"),
+            String::from("mari@example.com"),
+            30,
+            String::from("01/01/1990"),
+            crate::Categoria::Amigo,
+        ));
+        assert_eq!(ids("joa"), vec![0]);
+        assert_eq!(ids("mari"), vec![1]);
+        assert_ok!(CustomPallet::deletar_contato(RuntimeOrigin::signed(1), 0));
+        assert_eq!(ids("alva"), vec![2]);
+
+        assert_ok!(CustomPallet::do_try_state());
+        crate::IndiceBuscaContatos::<Test>::remove((1u64, frame_support::BoundedVec::truncate_from(b"mari".to_vec()), 1u32));
+        assert!(CustomPallet::do_try_state().is_err());
+    });
+}
+
+#[test]
+fn test_listar_contatos_ordenados_e_filtrados() {
+    use crate::{FiltroContatos, OrdemContatos};
+
+    new_test_ext().execute_with(|| {
+        for (nome, aniversario, categoria) in [
+            ("Carla", "15/03/1990", crate::Categoria::Amigo),
+            ("álvaro", "02/12/1985", crate::Categoria::Colega),
+            ("Bruno", "29/02/1992", crate::Categoria::Amigo),
+            ("Ana", "20/07/2000", crate::Categoria::Familiar),
+        ] {
+            assert_ok!(CustomPallet::criar_contato(
+                RuntimeOrigin::signed(1),
+                String::from(nome),
+                String::from("123456789"),
+                String::from("contato@example.com"),
+                30,
+                String::from(aniversario),
+                categoria,
+            ));
+        }
+        criar_contato_simples(2, "Zeca");
+
+        let ids = |filtro: FiltroContatos, ordem: OrdemContatos| -> Vec<u32> {
+            CustomPallet::listar_contatos(&1, filtro, ordem, None, 10).itens.iter().map(|contato| contato.id).collect()
+        };
+        assert_eq!(ids(FiltroContatos::default(), OrdemContatos::Id), vec![0, 1, 2, 3]);
+        assert_eq!(ids(FiltroContatos::default(), OrdemContatos::Nome), vec![1, 3, 2, 0]);
+        assert_eq!(ids(FiltroContatos::default(), OrdemContatos::Aniversario), vec![2, 0, 3, 1]);
+        assert_eq!(
+            ids(FiltroContatos { categoria: Some(crate::Categoria::Amigo) }, OrdemContatos::Nome),
+            vec![2, 0]
+        );
+        assert_eq!(CustomPallet::mes_e_dia(CustomPallet::contatos(1, 2).unwrap().data_aniversario), (2, 29));
+
+        // Percorre a lista em páginas de um item seguindo o cursor
+        let mut vistos = Vec::new();
+        let mut cursor = None;
+        loop {
+            let pagina = CustomPallet::listar_contatos(&1, FiltroContatos::default(), OrdemContatos::Nome, cursor, 1);
+            vistos.extend(pagina.itens.iter().map(|contato| contato.id));
+            match pagina.proximo {
+                Some(proximo) => cursor = Some(proximo),
+                None => break,
+            }
+        }
+        assert_eq!(vistos, vec![1, 3, 2, 0]);
+
+        // O cursor continua válido mesmo que o item dele seja removido
+        let pagina = CustomPallet::listar_contatos(&1, FiltroContatos::default(), OrdemContatos::Nome, None, 2);
+        assert_ok!(CustomPallet::deletar_contato(RuntimeOrigin::signed(1), 3));
+        let pagina = CustomPallet::listar_contatos(&1, FiltroContatos::default(), OrdemContatos::Nome, pagina.proximo, 2);
+        assert_eq!(pagina.itens.iter().map(|contato| contato.id).collect::<Vec<_>>(), vec![2, 0]);
+        assert_eq!(pagina.proximo, None);
+    });
+}
+
+#[test]
+fn test_listar_compromissos_ordenados_e_filtrados() {
+    use crate::{FiltroCompromissos, OrdemCompromissos};
+
+    new_test_ext().execute_with(|| {
+        for (titulo, data, hora, prioridade) in [
+            ("Dentista", "12/02/2025", "09:00", crate::Prioridade::Media),
+            ("Reunião", "10/02/2025", "14:00", crate::Prioridade::Alta),
+            ("Academia", "10/02/2025", "07:00", crate::Prioridade::Baixa),
+            ("Entrega", "11/02/2025", "18:00", crate::Prioridade::Alta),
+        ] {
+            assert_ok!(CustomPallet::criar_compromisso(
+                RuntimeOrigin::signed(1),
+                String::from(titulo),
+                String::from(data),
+                String::from(hora),
+                prioridade,
+                60,
+            ));
+        }
+        assert_ok!(CustomPallet::cancelar(RuntimeOrigin::signed(1), 3, Some(String::from("adiado"))));
+
+        let ids = |filtro: FiltroCompromissos, ordem: OrdemCompromissos| -> Vec<u32> {
+            CustomPallet::listar_compromissos(&1, filtro, ordem, None, 10)
+                .itens
+                .iter()
+                .map(|compromisso| compromisso.id)
+                .collect()
+        };
+        assert_eq!(ids(FiltroCompromissos::default(), OrdemCompromissos::Inicio), vec![2, 1, 3, 0]);
+        assert_eq!(ids(FiltroCompromissos::default(), OrdemCompromissos::Prioridade), vec![1, 3, 0, 2]);
+        assert_eq!(
+            ids(
+                FiltroCompromissos { prioridade: Some(crate::Prioridade::Alta), ..Default::default() },
+                OrdemCompromissos::Inicio
+            ),
+            vec![1, 3]
+        );
+        assert_eq!(
+            ids(FiltroCompromissos { status: Some(crate::Status::Agendado), ..Default::default() }, OrdemCompromissos::Id),
+            vec![0, 1, 2]
+        );
+
+        // Intervalo de datas: tudo que ocupa algum momento do dia 10/02
+        let segunda = CustomPallet::convert_to_timestamp(b"10/02/2025".to_vec()).unwrap();
+        let filtro = FiltroCompromissos { de: Some(segunda), ate: Some(segunda + 86400), ..Default::default() };
+        assert_eq!(ids(filtro.clone(), OrdemCompromissos::Inicio), vec![2, 1]);
+        // O compromisso que termina exatamente em `de` fica de fora
+        let filtro = FiltroCompromissos { de: Some(segunda + 8 * 3600), ..filtro };
+        assert_eq!(ids(filtro, OrdemCompromissos::Inicio), vec![1]);
+
+        let pagina = CustomPallet::listar_compromissos(&1, FiltroCompromissos::default(), OrdemCompromissos::Inicio, None, 3);
+        assert_eq!(pagina.itens.len(), 3);
+        let pagina =
+            CustomPallet::listar_compromissos(&1, FiltroCompromissos::default(), OrdemCompromissos::Inicio, pagina.proximo, 3);
+        assert_eq!(pagina.itens.iter().map(|compromisso| compromisso.id).collect::<Vec<_>>(), vec![0]);
+        assert_eq!(pagina.proximo, None);
+    });
+}
+
+fn novo_contato(nome: &str, data_aniversario: &str) -> crate::NovoContato {
+    crate::NovoContato {
+        nome: String::from(nome),
+        telefone: String::from("123456789"),
+        email: String::from("contato@example.com"),
+        idade: 30,
+        data_aniversario: String::from(data_aniversario),
+        categoria: crate::Categoria::Amigo,
+        notas: None,
+    }
+}
+
+#[test]
+fn test_lotes_de_contatos() {
+    use crate::ModoLote;
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+
+        // Melhor esforço: o item inválido é pulado e os demais gravados
+        assert_ok!(CustomPallet::criar_contatos(
+            RuntimeOrigin::signed(1),
+            vec![novo_contato("Ana", "01/01/1990"), novo_contato("Bia", "1990-01-01"), novo_contato("Caio", "02/02/1990")]
+                .try_into()
+                .unwrap(),
+            ModoLote::MelhorEsforco,
+        ));
+        System::assert_has_event(
+            crate::Event::ItemDoLoteFalhou { quem: 1, indice: 1, erro: crate::Error::<Test>::DataInvalida.into() }.into(),
+        );
+        System::assert_last_event(crate::Event::LoteConcluido { quem: 1, sucessos: 2, falhas: 1 }.into());
+        assert_eq!(CustomPallet::contatos(1, 1).unwrap().nome.to_vec(), b"Caio".to_vec());
+        assert_eq!(CustomPallet::total_contatos(1), 2);
+        assert_eq!(CustomPallet::escritas_na_janela(&1), 3);
+
+        // Tudo ou nada: a falha do segundo item é o erro da chamada e desfaz o primeiro, a cota e os eventos
+        assert_noop!(
+            CustomPallet::criar_contatos(
+                RuntimeOrigin::signed(1),
+                vec![novo_contato("Davi", "01/01/1990"), novo_contato("Eva", "32/01/1990")].try_into().unwrap(),
+                ModoLote::TudoOuNada,
+            ),
+            crate::Error::<Test>::DataInvalida
+        );
+        assert_eq!(CustomPallet::total_contatos(1), 2);
+        assert_eq!(CustomPallet::escritas_na_janela(&1), 3);
+        assert!(CustomPallet::buscar_contatos(&1, b"davi".to_vec(), None, 10).itens.is_empty());
+
+        assert_ok!(CustomPallet::atualizar_contatos(
+            RuntimeOrigin::signed(1),
+            vec![(0, novo_contato("Ana Lima", "01/01/1990")), (1, novo_contato("Caio Reis", "02/02/1990"))]
+                .try_into()
+                .unwrap(),
+            ModoLote::TudoOuNada,
+        ));
+        System::assert_last_event(crate::Event::LoteConcluido { quem: 1, sucessos: 2, falhas: 0 }.into());
+        assert_eq!(CustomPallet::buscar_contatos(&1, b"reis".to_vec(), None, 10).itens.len(), 1);
+
+        assert_ok!(CustomPallet::deletar_contatos(
+            RuntimeOrigin::signed(1),
+            vec![0, 7, 1].try_into().unwrap(),
+            ModoLote::MelhorEsforco,
+        ));
+        System::assert_has_event(crate::Event::ContatoDeletado { quem: 1, id: 1, contato: None }.into());
+        System::assert_has_event(
+            crate::Event::ItemDoLoteFalhou { quem: 1, indice: 1, erro: crate::Error::<Test>::ContatoNaoEncontrado.into() }
+                .into(),
+        );
+        System::assert_last_event(crate::Event::LoteConcluido { quem: 1, sucessos: 2, falhas: 1 }.into());
+        assert_eq!(CustomPallet::total_contatos(1), 0);
+
+        // A cota é cobrada por item, antes de aplicar o lote
+        crate::mock::MaxEscritasPorJanela::set(CustomPallet::escritas_na_janela(&1) + 2);
+        assert_noop!(
+            CustomPallet::deletar_contatos(RuntimeOrigin::signed(1), vec![0, 1, 2].try_into().unwrap(), ModoLote::MelhorEsforco),
+            crate::Error::<Test>::CotaDeEscritasExcedida
+        );
+
+        assert_ok!(CustomPallet::do_try_state());
+    });
+}
+
+#[test]
+fn test_lotes_de_compromissos() {
+    use crate::{ModoLote, NovoCompromisso};
+    use frame_support::{dispatch::GetDispatchInfo, sp_runtime::traits::Dispatchable};
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let novo = |titulo: &str, hora: &str| NovoCompromisso {
+            titulo: String::from(titulo),
+            data: String::from("10/02/2025"),
+            hora: String::from(hora),
+            prioridade: crate::Prioridade::Media,
+            duracao: 60,
+        };
+
+        // O terceiro tem hora inválida, e o erro dele chega no resultado do despacho
+        let chamada = crate::mock::RuntimeCall::CustomPallet(crate::Call::criar_compromissos {
+            itens: vec![novo("Reunião", "09:00"), novo("Almoço", "12:00"), novo("Ligação", "25:00")].try_into().unwrap(),
+            modo: ModoLote::TudoOuNada,
+        });
+        let resultado = chamada.dispatch(RuntimeOrigin::signed(1));
+        assert_eq!(resultado.map_err(|erro| erro.error), Err(crate::Error::<Test>::HoraInvalida.into()));
+        assert_eq!(CustomPallet::total_compromissos(1), 0);
+        assert_eq!(CustomPallet::escritas_na_janela(&1), 0);
+
+        assert_ok!(CustomPallet::criar_compromissos(
+            RuntimeOrigin::signed(1),
+            vec![novo("Reunião", "09:00"), novo("Almoço", "12:00"), novo("Ligação", "25:00")].try_into().unwrap(),
+            ModoLote::MelhorEsforco,
+        ));
+        System::assert_last_event(crate::Event::LoteConcluido { quem: 1, sucessos: 2, falhas: 1 }.into());
+
+        assert_ok!(CustomPallet::atualizar_compromissos(
+            RuntimeOrigin::signed(1),
+            vec![(1, novo("Almoço longo", "12:00"))].try_into().unwrap(),
+            ModoLote::TudoOuNada,
+        ));
+        assert_eq!(CustomPallet::compromissos(1, 1).unwrap().titulo.to_vec(), "Almoço longo".as_bytes().to_vec());
+
+        assert_ok!(CustomPallet::deletar_compromissos(
+            RuntimeOrigin::signed(1),
+            vec![0, 1].try_into().unwrap(),
+            ModoLote::TudoOuNada,
+        ));
+        assert_eq!(CustomPallet::total_compromissos(1), 0);
+
+        // O peso cresce com o tamanho do lote
+        let peso = |itens: u32| {
+            crate::Call::<Test>::deletar_compromissos {
+                ids: (0..itens).collect::<Vec<_>>().try_into().unwrap(),
+                modo: ModoLote::TudoOuNada,
+            }
+            .get_dispatch_info()
+            .weight
+        };
+        assert_eq!(peso(4) - peso(2), peso(3) - peso(1));
+        assert!(peso(5).ref_time() > peso(1).ref_time());
+
+        assert_ok!(CustomPallet::do_try_state());
+    });
+}
+
+#[test]
+fn test_cota_e_limites_dos_lotes_na_validacao() {
+    use crate::{extensao::VerificarCota, ModoLote};
+    use sp_runtime::traits::SignedExtension;
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        crate::TotalContatos::<Test>::insert(1, 8);
+        let chamada = |modo| -> crate::mock::RuntimeCall {
+            crate::Call::<Test>::criar_contatos {
+                itens: vec![novo_contato("Ana", "01/01/1990"); 3].try_into().unwrap(),
+                modo,
+            }
+            .into()
+        };
+        let info = Default::default();
+
+        // Três contatos não cabem, mas um lote de melhor esforço ainda grava os dois que cabem
+        assert!(VerificarCota::<Test>::new().validate(&1, &chamada(ModoLote::TudoOuNada), &info, 0).is_err());
+        assert_ok!(VerificarCota::<Test>::new().validate(&1, &chamada(ModoLote::MelhorEsforco), &info, 0));
+
+        crate::mock::MaxEscritasPorJanela::set(2);
+        assert!(VerificarCota::<Test>::new().validate(&2, &chamada(ModoLote::MelhorEsforco), &info, 0).is_err());
+    });
+}
+
+#[test]
+fn test_try_state_confere_datas_e_ids_livres() {
+    new_test_ext().execute_with(|| {
+        for nome in ["Ana", "Bia"] {
+            assert_ok!(CustomPallet::criar_contato(
+                RuntimeOrigin::signed(1),
+                String::from(nome),
+                String::from("123456789"),
+                String::from("ana@example.com"),
+                30,
+                String::from("01/01/1990"),
+                crate::Categoria::Amigo,
+            ));
+        }
+        assert_ok!(CustomPallet::deletar_contato(RuntimeOrigin::signed(1), 0));
+        assert_ok!(CustomPallet::criar_compromisso(
+            RuntimeOrigin::signed(1),
+            String::from("Reunião"),
+            String::from("10/02/2025"),
+            String::from("23:30"),
+            crate::Prioridade::Alta,
+            60,
+        ));
+        assert_ok!(CustomPallet::do_try_state());
+
+        // O fim guardado tem que ser o que as chamadas calculam a partir de data, hora e duração
+        crate::Compromissos::<Test>::mutate(1, 0, |compromisso| {
+            compromisso.as_mut().unwrap().data_fim -= 86400;
+        });
+        assert!(CustomPallet::do_try_state().is_err());
+        crate::Compromissos::<Test>::mutate(1, 0, |compromisso| {
+            compromisso.as_mut().unwrap().data_fim += 86400;
+        });
+        assert_ok!(CustomPallet::do_try_state());
+
+        // Ids livres só podem ser ids já emitidos pelo contador, sem repetição
+        crate::IdsLivresContatos::<Test>::insert(1, frame_support::BoundedVec::truncate_from(vec![0, 0]));
+        assert!(CustomPallet::do_try_state().is_err());
+        crate::IdsLivresContatos::<Test>::insert(1, frame_support::BoundedVec::truncate_from(vec![2]));
+        assert!(CustomPallet::do_try_state().is_err());
+        crate::IdsLivresContatos::<Test>::insert(1, frame_support::BoundedVec::truncate_from(vec![0]));
+        assert_ok!(CustomPallet::do_try_state());
+    });
+}
+
+#[test]
+fn test_notas_nos_lotes_de_contatos() {
+    use crate::ModoLote;
+
+    new_test_ext().execute_with(|| {
+        let com_notas = |nome: &str, notas: Option<&str>| crate::NovoContato {
+            notas: notas.map(String::from),
+            ..novo_contato(nome, "01/01/1990")
+        };
+
+        assert_ok!(CustomPallet::criar_contatos(
+            RuntimeOrigin::signed(1),
+            vec![com_notas("Ana", Some("Prefere ligação")), com_notas("Bia", None)].try_into().unwrap(),
+            ModoLote::TudoOuNada,
+        ));
+        assert_eq!(CustomPallet::contatos(1, 0).unwrap().notas.to_vec(), "Prefere ligação".as_bytes().to_vec());
+        assert!(CustomPallet::contatos(1, 1).unwrap().notas.is_empty());
+
+        // Notas longas demais são recusadas antes de o id ser alocado
+        assert_noop!(
+            CustomPallet::criar_contatos(
+                RuntimeOrigin::signed(1),
+                vec![com_notas("Caio", Some(&"a".repeat(201)))].try_into().unwrap(),
+                ModoLote::TudoOuNada,
+            ),
+            crate::Error::<Test>::NotasMuitoLongas
+        );
+
+        // Na atualização, `None` mantém as notas e `Some` as substitui
+        assert_ok!(CustomPallet::atualizar_contatos(
+            RuntimeOrigin::signed(1),
+            vec![(0, com_notas("Ana Lima", None)), (1, com_notas("Bia", Some("Vizinha")))].try_into().unwrap(),
+            ModoLote::TudoOuNada,
+        ));
+        assert_eq!(CustomPallet::contatos(1, 0).unwrap().notas.to_vec(), "Prefere ligação".as_bytes().to_vec());
+        assert_eq!(CustomPallet::contatos(1, 1).unwrap().notas.to_vec(), b"Vizinha".to_vec());
+
+        assert_ok!(CustomPallet::do_try_state());
+    });
+}
@@ -24,7 +24,7 @@
 // For more information, please refer to <http://unlicense.org>
 
 // External crates imports
-use alloc::{vec, vec::Vec};
+use alloc::vec::Vec;
 use frame_support::{
 	genesis_builder_helper::{build_state, get_preset},
 	weights::Weight,
@@ -42,8 +42,9 @@ use sp_version::RuntimeVersion;
 
 // Local module imports
 use super::{
-	AccountId, Aura, Balance, Block, Executive, Grandpa, InherentDataExt, Nonce, Runtime,
-	RuntimeCall, RuntimeGenesisConfig, SessionKeys, System, TransactionPayment, VERSION,
+	AccountId, Aura, Balance, Block, Executive, GCC1919AgendaPallet, Grandpa, InherentDataExt,
+	Nonce, Runtime, RuntimeCall, RuntimeGenesisConfig, SessionKeys, System, TransactionPayment,
+	VERSION,
 };
 
 impl_runtime_apis! {
@@ -279,17 +280,98 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl gcc1919_agenda_pallets::runtime_api::AgendaApi<
+		Block,
+		AccountId,
+		gcc1919_agenda_pallets::Contato<Runtime>,
+		gcc1919_agenda_pallets::Compromisso<Runtime>,
+		gcc1919_agenda_pallets::Tarefa<Runtime>,
+	> for Runtime {
+		fn disponibilidade(
+			contas: Vec<AccountId>,
+			inicio: u64,
+			fim: u64,
+			duracao: u32,
+		) -> gcc1919_agenda_pallets::Disponibilidade<AccountId> {
+			GCC1919AgendaPallet::disponibilidade(contas, inicio, fim, duracao)
+		}
+
+		fn compromissos_por_status(
+			conta: AccountId,
+			status: gcc1919_agenda_pallets::Status,
+		) -> Vec<gcc1919_agenda_pallets::Compromisso<Runtime>> {
+			GCC1919AgendaPallet::compromissos_por_status(&conta, status)
+		}
+
+		fn compromissos_com_contato(
+			conta: AccountId,
+			contato: u32,
+		) -> Vec<gcc1919_agenda_pallets::Compromisso<Runtime>> {
+			GCC1919AgendaPallet::compromissos_com_contato(&conta, contato)
+		}
+
+		fn tarefas_atrasadas(conta: AccountId) -> Vec<gcc1919_agenda_pallets::Tarefa<Runtime>> {
+			GCC1919AgendaPallet::tarefas_atrasadas(&conta)
+		}
+
+		fn buscar_contatos(
+			conta: AccountId,
+			consulta: Vec<u8>,
+			apos: Option<u32>,
+			limite: u32,
+		) -> gcc1919_agenda_pallets::Pagina<gcc1919_agenda_pallets::Contato<Runtime>> {
+			GCC1919AgendaPallet::buscar_contatos(&conta, consulta, apos, limite)
+		}
+
+		fn ids_contatos(conta: AccountId) -> Vec<u32> {
+			GCC1919AgendaPallet::ids_contatos(&conta)
+		}
+
+		fn contatos_por_ids(
+			conta: AccountId,
+			ids: Vec<u32>,
+		) -> Vec<gcc1919_agenda_pallets::Contato<Runtime>> {
+			GCC1919AgendaPallet::contatos_por_ids(&conta, ids)
+		}
+
+		fn listar_contatos(
+			conta: AccountId,
+			filtro: gcc1919_agenda_pallets::FiltroContatos,
+			ordem: gcc1919_agenda_pallets::OrdemContatos,
+			apos: Option<gcc1919_agenda_pallets::CursorLista>,
+			limite: u32,
+		) -> gcc1919_agenda_pallets::Pagina<
+			gcc1919_agenda_pallets::Contato<Runtime>,
+			gcc1919_agenda_pallets::CursorLista,
+		> {
+			GCC1919AgendaPallet::listar_contatos(&conta, filtro, ordem, apos, limite)
+		}
+
+		fn listar_compromissos(
+			conta: AccountId,
+			filtro: gcc1919_agenda_pallets::FiltroCompromissos,
+			ordem: gcc1919_agenda_pallets::OrdemCompromissos,
+			apos: Option<gcc1919_agenda_pallets::CursorLista>,
+			limite: u32,
+		) -> gcc1919_agenda_pallets::Pagina<
+			gcc1919_agenda_pallets::Compromisso<Runtime>,
+			gcc1919_agenda_pallets::CursorLista,
+		> {
+			GCC1919AgendaPallet::listar_compromissos(&conta, filtro, ordem, apos, limite)
+		}
+	}
+
 	impl sp_genesis_builder::GenesisBuilder<Block> for Runtime {
 		fn build_state(config: Vec<u8>) -> sp_genesis_builder::Result {
 			build_state::<RuntimeGenesisConfig>(config)
 		}
 
 		fn get_preset(id: &Option<sp_genesis_builder::PresetId>) -> Option<Vec<u8>> {
-			get_preset::<RuntimeGenesisConfig>(id, |_| None)
+			get_preset::<RuntimeGenesisConfig>(id, crate::genesis_config_presets::get_preset)
 		}
 
 		fn preset_names() -> Vec<sp_genesis_builder::PresetId> {
-			vec![]
+			crate::genesis_config_presets::preset_names()
 		}
 	}
 }
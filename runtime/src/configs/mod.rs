@@ -40,9 +40,9 @@ use sp_version::RuntimeVersion;
 
 // Local module imports
 use super::{
-	AccountId, Aura, Balance, Balances, Block, BlockNumber, Hash, Nonce, PalletInfo, Runtime,
+	AccountId, Aura, Balance, Balances, Block, BlockNumber, Hash, MultiBlockMigrations, Nonce, PalletInfo, Runtime,
 	RuntimeCall, RuntimeEvent, RuntimeFreezeReason, RuntimeHoldReason, RuntimeOrigin, RuntimeTask,
-	System, OriginCaller, EXISTENTIAL_DEPOSIT, SLOT_DURATION, VERSION,
+	System, Timestamp, OriginCaller, EXISTENTIAL_DEPOSIT, SLOT_DURATION, VERSION,
 };
 
 const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
@@ -88,6 +88,8 @@ impl frame_system::Config for Runtime {
 	/// This is used as an identifier of the chain. 42 is the generic substrate prefix.
 	type SS58Prefix = SS58Prefix;
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
+	/// Storage migrations that span several blocks; extrinsics are held back while one runs.
+	type MultiBlockMigrator = MultiBlockMigrations;
 }
 
 impl pallet_aura::Config for Runtime {
@@ -133,7 +135,7 @@ impl pallet_balances::Config for Runtime {
 	type FreezeIdentifier = RuntimeFreezeReason;
 	type MaxFreezes = VariantCountOf<RuntimeFreezeReason>;
 	type RuntimeHoldReason = RuntimeHoldReason;
-	type RuntimeFreezeReason = RuntimeHoldReason;
+	type RuntimeFreezeReason = RuntimeFreezeReason;
 }
 
 parameter_types! {
@@ -169,9 +171,35 @@ impl pallet_utility::Config for Runtime {
     type WeightInfo = pallet_utility::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	/// Share of each block that multi-block migrations may use.
+	pub MbmServiceWeight: Weight = Perbill::from_percent(80) * RuntimeBlockWeights::get().max_block;
+}
+
+impl pallet_migrations::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	/// The agenda storage migrations, run in order and stepped across as many blocks as needed.
+	#[cfg(not(feature = "runtime-benchmarks"))]
+	type Migrations = (
+		gcc1919_agenda_pallets::migrations::v1::MigrarParaV1<Runtime>,
+		gcc1919_agenda_pallets::migrations::v2::MigrarParaV2<Runtime>,
+		gcc1919_agenda_pallets::migrations::v3::MigrarParaV3<Runtime>,
+	);
+	#[cfg(feature = "runtime-benchmarks")]
+	type Migrations = pallet_migrations::mock_helpers::MockedMigrations;
+	type CursorMaxLen = ConstU32<65_536>;
+	type IdentifierMaxLen = ConstU32<256>;
+	type MigrationStatusHandler = ();
+	type FailedMigrationHandler = frame_support::migrations::FreezeChainOnFailedMigration;
+	type MaxServiceWeight = MbmServiceWeight;
+	type WeightInfo = pallet_migrations::weights::SubstrateWeight<Runtime>;
+}
+
 // Define counter max value runtime constant.
 parameter_types! {
     pub const CounterMaxValue: u32 = 500;
+    // Indexadores recebem o registro completo nos eventos da agenda
+    pub const VerbosidadeEventos: gcc1919_agenda_pallets::Verbosidade = gcc1919_agenda_pallets::Verbosidade::Completa;
 }
 
 // Configure custom pallet.
@@ -182,4 +210,37 @@ impl gcc1919_agenda_pallets::Config for Runtime {
     type MaxEmailLength = ConstU32<100>;
     type MaxTituloLength = ConstU32<10>;
 	type MaxHoraLength = ConstU32<5>;
+    type Tempo = Timestamp;
+    type MaxCapacidade = ConstU32<20>;
+    type RuntimeHoldReason = RuntimeHoldReason;
+    type Moeda = Balances;
+    type MaxMotivoLength = ConstU32<64>;
+    type MinDuracao = ConstU32<1>;
+    type MaxDuracao = ConstU32<{ 7 * 24 * 60 }>;
+    type MaxEnderecoLength = ConstU32<128>;
+    type MaxLinkLength = ConstU32<256>;
+    type MaxContatosPorCompromisso = ConstU32<16>;
+    type MaxItensChecklist = ConstU32<20>;
+    type MaxItemLength = ConstU32<64>;
+    type MaxAnexos = ConstU32<8>;
+    type MaxMimeLength = ConstU32<64>;
+    type MaxLocalizadorLength = ConstU32<128>;
+    type MaxDadosAnexo = ConstU32<{ 64 * 1024 }>;
+    type MaxNotasLength = ConstU32<1024>;
+    type MaxLembretes = ConstU32<8>;
+    type MaxHistoricoReagendamentos = ConstU32<10>;
+    type MaxPodaPorBloco = ConstU32<50>;
+    type MaxContatosPorConta = ConstU32<1000>;
+    type MaxCompromissosPorConta = ConstU32<5000>;
+    type ReutilizarIds = ConstBool<false>;
+    // Comporta importar uma agenda de 500 contatos em lotes e ainda sobra cota para o uso normal
+    type MaxEscritasPorJanela = ConstU32<1000>;
+    // Cerca de uma hora com blocos de seis segundos
+    type JanelaEscritas = ConstU32<600>;
+    type Verbosidade = VerbosidadeEventos;
+    type MaxPrefixoBusca = ConstU32<8>;
+    // O maior lote que ainda cabe em uma extrínseca com a limpeza de cada contato removido
+    type MaxLote = ConstU32<40>;
+    type MaxCompromissosPorContato = ConstU32<50>;
+    type MaxModelosPorConta = ConstU32<16>;
 }
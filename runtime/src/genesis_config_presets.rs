@@ -0,0 +1,246 @@
+// This is free and unencumbered software released into the public domain.
+//
+// Anyone is free to copy, modify, publish, use, compile, sell, or
+// distribute this software, either in source code form or as a compiled
+// binary, for any purpose, commercial or non-commercial, and by any
+// means.
+//
+// In jurisdictions that recognize copyright laws, the author or authors
+// of this software dedicate any and all copyright interest in the
+// software to the public domain. We make this dedication for the benefit
+// of the public at large and to the detriment of our heirs and
+// successors. We intend this dedication to be an overt act of
+// relinquishment in perpetuity of all present and future rights to this
+// software under copyright law.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+// IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY CLAIM, DAMAGES OR
+// OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE,
+// ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR
+// OTHER DEALINGS IN THE SOFTWARE.
+//
+// For more information, please refer to <http://unlicense.org>
+
+use crate::AccountId;
+use alloc::{vec, vec::Vec};
+use serde_json::Value;
+use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+use sp_consensus_grandpa::AuthorityId as GrandpaId;
+use sp_genesis_builder::{self, PresetId};
+use sp_keyring::{AccountKeyring, Ed25519Keyring, Sr25519Keyring};
+
+/// Preset with the development chain plus a realistic agenda dataset.
+pub const AGENDA_DEMO_PRESET: &str = "agenda_demo";
+
+// Returns the genesis config presets populated with given parameters.
+fn testnet_genesis(
+	initial_authorities: Vec<(AuraId, GrandpaId)>,
+	endowed_accounts: Vec<AccountId>,
+	root: AccountId,
+	agenda: Value,
+) -> Value {
+	serde_json::json!({
+		"balances": {
+			// Configure endowed accounts with initial balance of 1 << 60.
+			"balances": endowed_accounts.iter().cloned().map(|k| (k, 1u128 << 60)).collect::<Vec<_>>(),
+		},
+		"aura": {
+			"authorities": initial_authorities.iter().map(|x| x.0.clone()).collect::<Vec<_>>(),
+		},
+		"grandpa": {
+			"authorities": initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect::<Vec<_>>(),
+		},
+		"sudo": {
+			// Assign network admin rights.
+			"key": Some(root),
+		},
+		"gcc1919AgendaPallet": agenda,
+	})
+}
+
+fn contato(nome: &str, telefone: &str, email: &str, idade: u32, aniversario: &str, categoria: &str) -> Value {
+	serde_json::json!({
+		"nome": nome,
+		"telefone": telefone,
+		"email": email,
+		"idade": idade,
+		"dataAniversario": aniversario,
+		"categoria": categoria,
+	})
+}
+
+fn compromisso(titulo: &str, data: &str, hora: &str, prioridade: &str, duracao: u32) -> Value {
+	serde_json::json!({
+		"titulo": titulo,
+		"data": data,
+		"hora": hora,
+		"prioridade": prioridade,
+		"duracao": duracao,
+	})
+}
+
+// Small agendas for Alice and Bob, so local runs start with data to look at. Titles must fit the
+// runtime's `MaxTituloLength` (10 bytes), or building the genesis state panics.
+fn sample_agendas() -> Value {
+	let alice = AccountKeyring::Alice.to_account_id();
+	let bob = AccountKeyring::Bob.to_account_id();
+	serde_json::json!({
+		"contatos": [
+			[alice.clone(), contato("Bob", "11988887777", "bob@example.com", 32, "14/05/1994", "Colega")],
+			[alice.clone(), contato("Dra. Paula", "1133334444", "paula@clinica.example", 45, "02/09/1981", "Outro")],
+			[bob.clone(), contato("Alice", "11977776666", "alice@example.com", 29, "21/01/1997", "Colega")],
+		],
+		"compromissos": [
+			[alice.clone(), compromisso("Planejar", "03/11/2026", "10:00", "Alta", 60)],
+			[alice, compromisso("Consulta", "05/11/2026", "15:30", "Media", 30)],
+			[bob, compromisso("Planejar", "03/11/2026", "10:00", "Alta", 60)],
+		],
+	})
+}
+
+// A clinic (Alice), two patients (Bob, Charlie) and a family agenda (Dave) over two weeks. Dates,
+// birthdays included, can't be earlier than 1970.
+fn demo_agendas() -> Value {
+	let alice = AccountKeyring::Alice.to_account_id();
+	let bob = AccountKeyring::Bob.to_account_id();
+	let charlie = AccountKeyring::Charlie.to_account_id();
+	let dave = AccountKeyring::Dave.to_account_id();
+	serde_json::json!({
+		"contatos": [
+			[alice.clone(), contato("Bob Martins", "11988887777", "bob@example.com", 32, "14/05/1994", "Outro")],
+			[alice.clone(), contato("Charlie Souza", "11955554444", "charlie@example.com", 41, "30/11/1985", "Outro")],
+			[alice.clone(), contato("Laboratório Central", "1132100000", "agenda@lab.example", 0, "01/01/2000", "Outro")],
+			[alice.clone(), contato("Marina (recepção)", "11944443333", "marina@clinica.example", 27, "08/07/1999", "Colega")],
+			[bob.clone(), contato("Clínica Alice", "1133334444", "contato@clinica.example", 0, "01/01/2000", "Outro")],
+			[bob.clone(), contato("Dave", "11922221111", "dave@example.com", 35, "19/02/1991", "Amigo")],
+			[charlie.clone(), contato("Clínica Alice", "1133334444", "contato@clinica.example", 0, "01/01/2000", "Outro")],
+			[dave.clone(), contato("Bob", "11988887777", "bob@example.com", 32, "14/05/1994", "Amigo")],
+			[dave.clone(), contato("Mãe", "11911112222", "mae@example.com", 55, "25/12/1970", "Familiar")],
+			[dave.clone(), contato("Escola Aurora", "1130304040", "secretaria@escola.example", 0, "01/01/2000", "Outro")],
+		],
+		"compromissos": [
+			[alice.clone(), compromisso("Consulta", "03/11/2026", "09:00", "Media", 30)],
+			[alice.clone(), compromisso("Consulta", "03/11/2026", "09:30", "Media", 30)],
+			[alice.clone(), compromisso("Equipe", "04/11/2026", "08:00", "Alta", 60)],
+			[alice.clone(), compromisso("Retorno", "10/11/2026", "14:00", "Baixa", 20)],
+			[alice.clone(), compromisso("Exames", "12/11/2026", "07:30", "Alta", 90)],
+			[bob.clone(), compromisso("Consulta", "03/11/2026", "09:00", "Media", 30)],
+			[bob.clone(), compromisso("Academia", "05/11/2026", "18:30", "Baixa", 60)],
+			[bob.clone(), compromisso("Retorno", "10/11/2026", "14:00", "Baixa", 20)],
+			[charlie.clone(), compromisso("Consulta", "03/11/2026", "09:30", "Media", 30)],
+			[charlie, compromisso("Relatório", "06/11/2026", "17:00", "Alta", 45)],
+			[dave.clone(), compromisso("Escola", "04/11/2026", "19:00", "Media", 60)],
+			[dave.clone(), compromisso("Almoço", "08/11/2026", "12:30", "Media", 120)],
+			[dave, compromisso("Futebol", "13/11/2026", "20:00", "Baixa", 90)],
+		],
+	})
+}
+
+fn dev_endowed_accounts() -> Vec<AccountId> {
+	vec![
+		AccountKeyring::Alice.to_account_id(),
+		AccountKeyring::Bob.to_account_id(),
+		AccountKeyring::AliceStash.to_account_id(),
+		AccountKeyring::BobStash.to_account_id(),
+	]
+}
+
+/// Return the development genesis config.
+pub fn development_config_genesis() -> Value {
+	testnet_genesis(
+		vec![(Sr25519Keyring::Alice.public().into(), Ed25519Keyring::Alice.public().into())],
+		dev_endowed_accounts(),
+		AccountKeyring::Alice.to_account_id(),
+		sample_agendas(),
+	)
+}
+
+/// Return the local genesis config preset.
+pub fn local_config_genesis() -> Value {
+	testnet_genesis(
+		vec![
+			(Sr25519Keyring::Alice.public().into(), Ed25519Keyring::Alice.public().into()),
+			(Sr25519Keyring::Bob.public().into(), Ed25519Keyring::Bob.public().into()),
+		],
+		AccountKeyring::iter()
+			.filter(|v| v != &AccountKeyring::One && v != &AccountKeyring::Two)
+			.map(|v| v.to_account_id())
+			.collect::<Vec<_>>(),
+		AccountKeyring::Alice.to_account_id(),
+		sample_agendas(),
+	)
+}
+
+/// Return the development genesis config with the agenda demo dataset.
+pub fn agenda_demo_config_genesis() -> Value {
+	let mut endowed_accounts = dev_endowed_accounts();
+	endowed_accounts.push(AccountKeyring::Charlie.to_account_id());
+	endowed_accounts.push(AccountKeyring::Dave.to_account_id());
+	testnet_genesis(
+		vec![(Sr25519Keyring::Alice.public().into(), Ed25519Keyring::Alice.public().into())],
+		endowed_accounts,
+		AccountKeyring::Alice.to_account_id(),
+		demo_agendas(),
+	)
+}
+
+/// Provides the JSON representation of predefined genesis config for given `id`.
+pub fn get_preset(id: &PresetId) -> Option<Vec<u8>> {
+	let patch = match id.try_into() {
+		Ok(sp_genesis_builder::DEV_RUNTIME_PRESET) => development_config_genesis(),
+		Ok(sp_genesis_builder::LOCAL_TESTNET_RUNTIME_PRESET) => local_config_genesis(),
+		Ok(AGENDA_DEMO_PRESET) => agenda_demo_config_genesis(),
+		_ => return None,
+	};
+	Some(
+		serde_json::to_string(&patch)
+			.expect("serialization to json is expected to work. qed.")
+			.into_bytes(),
+	)
+}
+
+/// List of supported presets.
+pub fn preset_names() -> Vec<PresetId> {
+	vec![
+		PresetId::from(sp_genesis_builder::DEV_RUNTIME_PRESET),
+		PresetId::from(sp_genesis_builder::LOCAL_TESTNET_RUNTIME_PRESET),
+		PresetId::from(AGENDA_DEMO_PRESET),
+	]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Block, Runtime, RuntimeGenesisConfig};
+	use sp_genesis_builder::runtime_decl_for_genesis_builder::GenesisBuilderV1 as GenesisBuilder;
+
+	// Applies a preset patch over the default config, like the node does when building a chain spec
+	fn merge(base: &mut Value, patch: Value) {
+		match (base, patch) {
+			(Value::Object(base), Value::Object(patch)) =>
+				for (key, value) in patch {
+					merge(base.entry(key).or_insert(Value::Null), value);
+				},
+			(base, patch) => *base = patch,
+		}
+	}
+
+	fn build_preset(id: &PresetId) -> sp_genesis_builder::Result {
+		let patch = <Runtime as GenesisBuilder<Block>>::get_preset(&Some(id.clone())).expect("preset exists");
+		let mut config = serde_json::to_value(RuntimeGenesisConfig::default()).unwrap();
+		merge(&mut config, serde_json::from_slice(&patch).unwrap());
+		sp_io::TestExternalities::default()
+			.execute_with(|| <Runtime as GenesisBuilder<Block>>::build_state(serde_json::to_vec(&config).unwrap()))
+	}
+
+	#[test]
+	fn every_preset_builds() {
+		let presets = <Runtime as GenesisBuilder<Block>>::preset_names();
+		assert_eq!(presets, preset_names());
+		for id in presets {
+			assert_eq!(build_preset(&id), Ok(()), "preset {}", id);
+		}
+	}
+}
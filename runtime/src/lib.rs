@@ -7,6 +7,7 @@ pub mod apis;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarks;
 pub mod configs;
+pub mod genesis_config_presets;
 
 extern crate alloc;
 use alloc::vec::Vec;
@@ -67,10 +68,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: apis::RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 	state_version: 1,
 };
 
@@ -155,6 +156,7 @@ pub type SignedExtra = (
 	frame_system::CheckWeight<Runtime>,
 	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
 	frame_metadata_hash_extension::CheckMetadataHash<Runtime>,
+	gcc1919_agenda_pallets::extensao::VerificarCota<Runtime>,
 );
 
 /// Unchecked extrinsic type as expected by this runtime.
@@ -166,7 +168,8 @@ pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
 
 /// All migrations of the runtime, aside from the ones declared in the pallets.
 ///
-/// This can be a tuple of types, each implementing `OnRuntimeUpgrade`.
+/// This can be a tuple of types, each implementing `OnRuntimeUpgrade`. Migrations that may not
+/// fit in one block go to `pallet_migrations::Config::Migrations` instead.
 #[allow(unused_parens)]
 type Migrations = ();
 
@@ -227,4 +230,60 @@ mod runtime {
 
 	#[runtime::pallet_index(52)]
 	pub type GCC1919AgendaPallet = gcc1919_agenda_pallets;
+
+	#[runtime::pallet_index(53)]
+	pub type MultiBlockMigrations = pallet_migrations;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::{
+		migrations::MultiStepMigrator,
+		traits::{GetStorageVersion, IntegrityTest, OnRuntimeUpgrade, StorageVersion},
+	};
+	use gcc1919_agenda_pallets::migrations::v1::ContatoV0;
+
+	#[test]
+	fn every_pallet_passes_integrity_test() {
+		sp_io::TestExternalities::default().execute_with(AllPalletsWithSystem::integrity_test);
+	}
+
+	#[test]
+	fn agenda_migrations_run_across_blocks() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			StorageVersion::new(0).put::<GCC1919AgendaPallet>();
+			let dono = AccountId::from([1u8; 32]);
+			for id in 0..3u32 {
+				let contato = ContatoV0::<Runtime> {
+					id,
+					nome: b"Contato".to_vec().try_into().unwrap(),
+					telefone: b"123456789".to_vec().try_into().unwrap(),
+					email: b"contato@example.com".to_vec().try_into().unwrap(),
+					idade: 30,
+					data_aniversario: 0,
+					categoria: gcc1919_agenda_pallets::Categoria::Amigo,
+				};
+				frame_support::storage::unhashed::put(
+					&gcc1919_agenda_pallets::Contatos::<Runtime>::hashed_key_for(&dono, id),
+					&contato,
+				);
+			}
+
+			// The upgrade only starts the migrations; they are stepped once per block
+			MultiBlockMigrations::on_runtime_upgrade();
+			assert!(MultiBlockMigrations::ongoing());
+			let mut blocos = 0;
+			while MultiBlockMigrations::ongoing() {
+				blocos += 1;
+				System::set_block_number(blocos);
+				MultiBlockMigrations::step();
+				assert!(blocos < 10, "migrations did not finish");
+			}
+
+			assert_eq!(GCC1919AgendaPallet::on_chain_storage_version(), 3);
+			assert_eq!(gcc1919_agenda_pallets::TotalContatos::<Runtime>::get(&dono), 3);
+			assert!(gcc1919_agenda_pallets::Contatos::<Runtime>::get(&dono, 2).is_some());
+		});
+	}
 }